@@ -0,0 +1,115 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+// an array of records with mostly unescaped strings.
+fn records() -> String {
+    let mut s = String::from("[");
+    for i in 0..1000 {
+        if i > 0 {
+            s.push(',');
+        }
+        s.push_str(&format!(
+            r#"{{"id": {}, "name": "user{}", "email": "user{}@example.com", "tags": ["a", "b", "c"], "bio": "line\nbreak", "score": {}.5}}"#,
+            i, i, i, i
+        ));
+    }
+    s.push(']');
+    s
+}
+
+// long strings, as in text-heavy documents.
+fn strings() -> String {
+    let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+    let items: Vec<String> = (0..200)
+        .map(|i| format!(r#"{{"title": "post {}", "body": "{}"}}"#, i, text))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+// deeply indented pretty-printed output.
+fn pretty() -> String {
+    let mut s = String::from("[\n");
+    for i in 0..1000 {
+        let indent = "        ";
+        s.push_str(&format!(
+            "{0}{{\n{0}{0}\"id\": {1},\n{0}{0}\"values\": [\n{0}{0}{0}{1},\n{0}{0}{0}true\n{0}{0}]\n{0}}}{2}\n",
+            indent,
+            i,
+            if i < 999 { "," } else { "" }
+        ));
+    }
+    s.push(']');
+    s
+}
+
+// long integers and floats.
+fn numbers() -> String {
+    let items: Vec<String> = (0..5000)
+        .map(|i| format!("{}, -{}.{}e-{}", i * 7919 * 1_000_003, i, i * 31, i % 300))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn parse(c: &mut Criterion) {
+    let inputs = vec![
+        ("records", records()),
+        ("strings", strings()),
+        ("pretty", pretty()),
+        ("numbers", numbers()),
+    ];
+    for (name, s) in inputs {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(s.len() as u64));
+        group.bench_function("owned", |b| {
+            b.iter(|| json::from_slice(black_box(s.as_bytes())).unwrap())
+        });
+        group.bench_function("borrowed", |b| {
+            b.iter(|| json::from_slice_borrowed(black_box(s.as_bytes())).unwrap())
+        });
+        group.finish();
+    }
+}
+
+type Scan = fn(&[u8]) -> usize;
+
+// the SSE2 scans against the byte-at-a-time ones in `scan::scalar`, on the same inputs.
+fn scan(c: &mut Criterion) {
+    use json::scan::{self, scalar};
+
+    let inputs = vec![("records", records()), ("pretty", pretty())];
+    for (name, s) in inputs {
+        let bs = s.as_bytes();
+        let mut group = c.benchmark_group(format!("index/{}", name));
+        group.throughput(Throughput::Bytes(bs.len() as u64));
+        group.bench_function("sse2", |b| b.iter(|| scan::structural_index(black_box(bs))));
+        group.bench_function("scalar", |b| {
+            b.iter(|| scalar::structural_index(black_box(bs)))
+        });
+        group.finish();
+    }
+
+    let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+    let spaces = format!("{}x", " \n    ".repeat(200));
+    let digits = "1234567890".repeat(10);
+    let runs: Vec<(&str, &str, Scan, Scan)> = vec![
+        (
+            "string_end",
+            &text,
+            |bs| scan::string_end(bs, b'"'),
+            |bs| scalar::string_end(bs, b'"'),
+        ),
+        ("whitespace", &spaces, scan::whitespace, scalar::whitespace),
+        ("digits", &digits, scan::digits, scalar::digits),
+    ];
+    for (name, s, sse2, scalar) in runs {
+        let bs = s.as_bytes();
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(bs.len() as u64));
+        group.bench_function("sse2", |b| b.iter(|| sse2(black_box(bs))));
+        group.bench_function("scalar", |b| b.iter(|| scalar(black_box(bs))));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse, scan);
+criterion_main!(benches);
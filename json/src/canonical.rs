@@ -0,0 +1,104 @@
+// canonical JSON (RFC 8785 JCS). equal documents are encoded into identical bytes,
+// so the output can be hashed or signed.
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use super::error::Err;
+use super::serializer::write_string;
+use super::value::Value;
+
+// fails for NaN and infinities, which have no JSON representation.
+pub fn to_string(v: &Value) -> Result<String, Err> {
+    let mut s = String::new();
+    write_value(&mut s, v)?;
+    Ok(s)
+}
+
+// SHA-256 digest of the canonical encoding.
+pub fn hash(v: &Value) -> Result<[u8; 32], Err> {
+    Ok(Sha256::digest(to_string(v)?.as_bytes()).into())
+}
+
+fn write_value(s: &mut String, v: &Value) -> Result<(), Err> {
+    match v {
+        Value::Number(n) => {
+            // numbers are IEEE 754 doubles in JCS, as in ECMAScript.
+            let f = n.as_f64().filter(|f| f.is_finite());
+            write_number(s, f.ok_or_else(|| Err::out_of_range("canonical JSON"))?);
+        }
+        Value::Array(arr) => {
+            s.push('[');
+            for (i, v) in arr.iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                write_value(s, v)?;
+            }
+            s.push(']');
+        }
+        Value::Object(obj) => {
+            let mut members: Vec<_> = obj.iter().collect();
+            members.sort_by(|a, b| cmp_utf16(a.0, b.0));
+            s.push('{');
+            for (i, (k, v)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                write_string(s, k).unwrap();
+                s.push(':');
+                write_value(s, v)?;
+            }
+            s.push('}');
+        }
+        // the other values are written the same as by Display.
+        v => write!(s, "{}", v).unwrap(),
+    }
+    Ok(())
+}
+
+// keys are sorted by their UTF-16 code units, not by code points.
+fn cmp_utf16(a: &str, b: &str) -> Ordering {
+    a.encode_utf16().cmp(b.encode_utf16())
+}
+
+// formats a finite number as ECMAScript's Number.prototype.toString does.
+fn write_number(s: &mut String, f: f64) {
+    if f == 0.0 {
+        // including -0.
+        s.push('0');
+        return;
+    }
+    if f < 0.0 {
+        s.push('-');
+    }
+
+    // shortest round-trip digits and the decimal exponent, from e.g. "1.2345e-7".
+    let e = format!("{:e}", f.abs());
+    let (mantissa, exp) = e.split_at(e.find('e').unwrap());
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let k = digits.len() as i32;
+    // the value is 0.digits * 10^n.
+    let n = exp[1..].parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        s.push_str(&digits);
+        s.extend((0..n - k).map(|_| '0'));
+    } else if 0 < n && n <= 21 {
+        s.push_str(&digits[..n as usize]);
+        s.push('.');
+        s.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        s.push_str("0.");
+        s.extend((0..-n).map(|_| '0'));
+        s.push_str(&digits);
+    } else {
+        s.push_str(&digits[..1]);
+        if k > 1 {
+            s.push('.');
+            s.push_str(&digits[1..]);
+        }
+        write!(s, "e{}{}", if n > 0 { "+" } else { "-" }, (n - 1).abs()).unwrap();
+    }
+}
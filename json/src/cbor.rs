@@ -0,0 +1,359 @@
+// CBOR (RFC 8949) encoding of values. byte strings, tags, undefined and other simple values
+// have no JSON equivalent and fail to decode, as do maps with non-string keys.
+use std::collections::HashMap;
+use std::mem;
+use std::str;
+
+use super::error::{Err, ErrKind};
+use super::number::Number;
+use super::parser::Limits;
+use super::reader::{BytesReader, Reader};
+use super::value::Value;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+// additional information of the indefinite-length items, and the byte ending them.
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xFF;
+
+pub fn to_vec(v: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_value(&mut buf, v);
+    buf
+}
+
+pub fn from_slice(bs: &[u8]) -> Result<Value, Err> {
+    from_slice_with_limits(bs, Limits::default())
+}
+
+// decodes with the same limits as text JSON.
+pub fn from_slice_with_limits(bs: &[u8], limits: Limits) -> Result<Value, Err> {
+    if bs.len() > limits.max_input_size {
+        return Err(Err::from_kind(ErrKind::InputTooLarge));
+    }
+    let mut d = Decoder {
+        r: BytesReader::new(bs),
+        limits,
+    };
+    let v = d.decode()?;
+    match d.r.cur() {
+        None => Ok(v),
+        Some(_) => Err(Err::new()),
+    }
+}
+
+fn write_value(buf: &mut Vec<u8>, v: &Value) {
+    match v {
+        Value::Null => buf.push(0xF6),
+        Value::Boolean(false) => buf.push(0xF4),
+        Value::Boolean(true) => buf.push(0xF5),
+        Value::Number(n) => write_number(buf, n),
+        Value::String(s) => {
+            write_head(buf, TEXT, s.len() as u64);
+            buf.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(arr) => {
+            write_head(buf, ARRAY, arr.len() as u64);
+            for v in arr {
+                write_value(buf, v);
+            }
+        }
+        Value::Object(obj) => {
+            write_head(buf, MAP, obj.len() as u64);
+            for (k, v) in obj.iter() {
+                write_head(buf, TEXT, k.len() as u64);
+                buf.extend_from_slice(k.as_bytes());
+                write_value(buf, v);
+            }
+        }
+    }
+}
+
+fn write_number(buf: &mut Vec<u8>, n: &Number) {
+    if let Some(u) = n.as_u64() {
+        write_head(buf, UNSIGNED, u);
+    } else if let Some(i) = n.as_i64() {
+        write_head(buf, NEGATIVE, !(i as u64));
+    } else {
+        // other numbers, including arbitrary precision ones, are written as floats.
+        let f = n.as_f64().unwrap_or(f64::NAN);
+        if f as f32 as f64 == f || f.is_nan() {
+            buf.push(SIMPLE << 5 | 26);
+            buf.extend_from_slice(&(f as f32).to_be_bytes());
+        } else {
+            buf.push(SIMPLE << 5 | 27);
+            buf.extend_from_slice(&f.to_be_bytes());
+        }
+    }
+}
+
+// writes the major type with the argument in the shortest form.
+fn write_head(buf: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        buf.push(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        buf.extend_from_slice(&[major | 24, n as u8]);
+    } else if n <= u16::MAX as u64 {
+        buf.push(major | 25);
+        buf.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= u32::MAX as u64 {
+        buf.push(major | 26);
+        buf.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        buf.push(major | 27);
+        buf.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    r: BytesReader<'a>,
+    limits: Limits,
+}
+
+// an array or map being decoded, with the number of items left, or None for an
+// indefinite-length one, which ends at a break.
+enum Frame {
+    Array(Vec<Value>, Option<usize>),
+    // members so far, the members left, the number of members read, and the key of the
+    // member being decoded.
+    Map(HashMap<String, Value>, Option<usize>, usize, String),
+}
+
+impl<'a> Decoder<'a> {
+    // arrays and maps are kept on a stack rather than decoded recursively, as in the parser.
+    fn decode(&mut self) -> Result<Value, Err> {
+        let mut stack = Vec::new();
+        loop {
+            let mut done = self.item(&mut stack)?;
+            loop {
+                if let Some(v) = done.take() {
+                    match stack.last_mut() {
+                        None => return Ok(v),
+                        Some(Frame::Array(arr, _)) => arr.push(v),
+                        Some(Frame::Map(obj, .., key)) => {
+                            obj.insert(mem::take(key), v);
+                        }
+                    }
+                }
+                match self.next(&mut stack)? {
+                    Some(v) => done = Some(v),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    // the next item, or None when it opens an array or map.
+    fn item(&mut self, stack: &mut Vec<Frame>) -> Result<Option<Value>, Err> {
+        let b = self.byte()?;
+        let (major, info) = (b >> 5, b & 0x1F);
+        let frame = match major {
+            UNSIGNED => return Ok(Some(Value::Number(Number::from(self.argument(info)?)))),
+            NEGATIVE => {
+                let n = self.argument(info)?;
+                if n <= i64::MAX as u64 {
+                    return Ok(Some(Value::Number(Number::from(-1 - n as i64))));
+                }
+                // below i64::MIN, the same as such a number in text.
+                let s = format!("-{}", n as u128 + 1);
+                return Number::from_json_str(&s)
+                    .map(|n| Some(Value::Number(n)))
+                    .ok_or_else(Err::new);
+            }
+            BYTES => return Err(unsupported("byte string")),
+            TEXT => return self.text(info).map(|s| Some(Value::String(s))),
+            ARRAY => Frame::Array(Vec::new(), self.length(info)?),
+            MAP => {
+                let len = self.length(info)?;
+                Frame::Map(HashMap::new(), len, 0, String::new())
+            }
+            TAG => return Err(unsupported("tag")),
+            _ => return self.simple(info).map(Some),
+        };
+        if stack.len() >= self.limits.max_depth {
+            return Err(Err::from_kind(ErrKind::DepthLimitExceeded));
+        }
+        if let Frame::Map(_, Some(len), ..) = frame {
+            self.check_members(len)?;
+        }
+        stack.push(frame);
+        Ok(None)
+    }
+
+    // ends the array or map on top of the stack if it has no items left, or else reads up
+    // to its next item.
+    fn next(&mut self, stack: &mut Vec<Frame>) -> Result<Option<Value>, Err> {
+        let more = match stack.last_mut() {
+            Some(Frame::Array(_, left)) | Some(Frame::Map(_, left, ..)) => match left {
+                Some(0) => false,
+                Some(n) => {
+                    *n -= 1;
+                    true
+                }
+                None => !self.at_break()?,
+            },
+            None => false,
+        };
+        if more {
+            if let Some(Frame::Map(_, _, len, key)) = stack.last_mut() {
+                *len += 1;
+                self.check_members(*len)?;
+                *key = self.key()?;
+            }
+            return Ok(None);
+        }
+        Ok(match stack.pop() {
+            Some(Frame::Array(arr, _)) => Some(Value::Array(arr)),
+            Some(Frame::Map(obj, ..)) => Some(Value::Object(Box::new(obj))),
+            None => None,
+        })
+    }
+
+    fn simple(&mut self, info: u8) -> Result<Value, Err> {
+        match info {
+            20 => Ok(Value::Boolean(false)),
+            21 => Ok(Value::Boolean(true)),
+            22 => Ok(Value::Null),
+            23 => Err(unsupported("undefined")),
+            25 => {
+                let bs = self.take(2)?;
+                Ok(Value::from(f16_to_f64(u16::from_be_bytes([bs[0], bs[1]]))))
+            }
+            26 => {
+                let bs = self.take(4)?;
+                Ok(Value::from(f32::from_be_bytes([
+                    bs[0], bs[1], bs[2], bs[3],
+                ])))
+            }
+            27 => {
+                let mut b = [0; 8];
+                b.copy_from_slice(self.take(8)?);
+                Ok(Value::from(f64::from_be_bytes(b)))
+            }
+            // a break outside of an indefinite-length item.
+            INDEFINITE => Err(Err::new()),
+            _ => Err(unsupported("simple value")),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, Err> {
+        let b = self.byte()?;
+        match b >> 5 {
+            TEXT => self.text(b & 0x1F),
+            _ => Err(unsupported("non-string map key")),
+        }
+    }
+
+    fn text(&mut self, info: u8) -> Result<String, Err> {
+        match self.length(info)? {
+            Some(len) => {
+                self.check_string(len)?;
+                let bs = self.take(len)?;
+                str::from_utf8(bs).map(String::from).map_err(|_| Err::new())
+            }
+            // an indefinite-length string is a sequence of definite-length chunks.
+            None => {
+                let mut s = String::new();
+                while !self.at_break()? {
+                    let b = self.byte()?;
+                    if b >> 5 != TEXT || b & 0x1F == INDEFINITE {
+                        return Err(Err::new());
+                    }
+                    s.push_str(&self.text(b & 0x1F)?);
+                    self.check_string(s.len())?;
+                }
+                Ok(s)
+            }
+        }
+    }
+
+    fn check_string(&self, len: usize) -> Result<(), Err> {
+        if len > self.limits.max_string_length {
+            return Err(Err::from_kind(ErrKind::StringTooLong));
+        }
+        Ok(())
+    }
+
+    fn check_members(&self, len: usize) -> Result<(), Err> {
+        if len > self.limits.max_object_members {
+            return Err(Err::from_kind(ErrKind::TooManyMembers));
+        }
+        Ok(())
+    }
+
+    // length of a string, array or map. None for indefinite length.
+    fn length(&mut self, info: u8) -> Result<Option<usize>, Err> {
+        if info == INDEFINITE {
+            return Ok(None);
+        }
+        let n = self.argument(info)?;
+        // every item takes a byte at least, so longer lengths can't be valid.
+        match self.r.rest().len() as u64 {
+            rest if n <= rest => Ok(Some(n as usize)),
+            _ => Err(Err::new()),
+        }
+    }
+
+    fn argument(&mut self, info: u8) -> Result<u64, Err> {
+        let n = match info {
+            0..=23 => return Ok(info as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(Err::new()),
+        };
+        Ok(self.take(n)?.iter().fold(0, |acc, &b| acc << 8 | b as u64))
+    }
+
+    // consumes the break ending an indefinite-length item, if it is next.
+    fn at_break(&mut self) -> Result<bool, Err> {
+        match self.r.cur() {
+            Some(BREAK) => {
+                self.r.mov(1);
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(Err::new()),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, Err> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Err> {
+        let bs = self.r.slice(n).ok_or_else(Err::new)?;
+        self.r.mov(n);
+        Ok(bs)
+    }
+}
+
+fn unsupported(ty: &'static str) -> Err {
+    Err::from_kind(ErrKind::Unsupported(ty))
+}
+
+// IEEE 754 half precision, as in RFC 8949 appendix D.
+fn f16_to_f64(half: u16) -> f64 {
+    let exp = (half >> 10) & 0x1F;
+    let mant = (half & 0x3FF) as f64;
+    let val = match exp {
+        0 => mant * 2f64.powi(-24),
+        31 if mant == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mant + 1024.0) * 2f64.powi(exp as i32 - 25),
+    };
+    if half & 0x8000 != 0 {
+        -val
+    } else {
+        val
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+use super::error::Err;
+use super::number::Number;
+use super::value::Value;
+
+// converts a Rust value into a JSON value.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+// converts a JSON value into a Rust value. errors carry the pointer of the failed value.
+// `'a` is the lifetime of the converted value, which borrowed types like `&'a str` point into.
+pub trait FromJson<'a>: Sized {
+    fn from_json(v: &'a Value) -> Result<Self, Err>;
+
+    // the value of a missing object member. only Option has one by default.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+// a type which borrows nothing from the converted value.
+pub trait FromJsonOwned: for<'a> FromJson<'a> {}
+
+impl<T> FromJsonOwned for T where T: for<'a> FromJson<'a> {}
+
+impl Value {
+    // converts the member of an object.
+    pub fn field<'a, T: FromJson<'a>>(&'a self, key: &str) -> Result<T, Err> {
+        let obj = match self {
+            Value::Object(obj) => obj,
+            v => return Err(Err::invalid_type("object", v.type_name())),
+        };
+        match obj.get(key) {
+            Some(v) => T::from_json(v).map_err(|e| e.at(key)),
+            None => T::from_missing().ok_or_else(|| Err::missing_field(key)),
+        }
+    }
+}
+
+// converts an integral number which fits into `T`.
+pub(crate) fn to_integer<T>(n: &Number, ty: &'static str) -> Result<T, Err>
+where
+    T: TryFrom<i64> + TryFrom<u64>,
+{
+    // a float is the wrong kind of number, not one out of range.
+    if n.is_f64() {
+        return Err(Err::invalid_type(ty, "number"));
+    }
+    n.as_i64()
+        .and_then(|n| T::try_from(n).ok())
+        .or_else(|| n.as_u64().and_then(|n| T::try_from(n).ok()))
+        .ok_or_else(|| Err::out_of_range(ty))
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl<'a> FromJson<'a> for Value {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        Ok(v.clone())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Boolean(*self)
+    }
+}
+
+impl<'a> FromJson<'a> for bool {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::Boolean(b) => Ok(*b),
+            v => Err(Err::invalid_type("boolean", v.type_name())),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl<'a> FromJson<'a> for String {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::String(s) => Ok(s.clone()),
+            v => Err(Err::invalid_type("string", v.type_name())),
+        }
+    }
+}
+
+impl<'a> FromJson<'a> for &'a str {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::String(s) => Ok(s),
+            v => Err(Err::invalid_type("string", v.type_name())),
+        }
+    }
+}
+
+macro_rules! impl_json_for_integers {
+    ( $( $t:ident ),* ) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+
+            impl<'a> FromJson<'a> for $t {
+                fn from_json(v: &'a Value) -> Result<Self, Err> {
+                    match v {
+                        Value::Number(n) => to_integer(n, stringify!($t)),
+                        v => Err(Err::invalid_type("number", v.type_name())),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_json_for_integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_json_for_floats {
+    ( $( $t:ident ),* ) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+
+            impl<'a> FromJson<'a> for $t {
+                fn from_json(v: &'a Value) -> Result<Self, Err> {
+                    let n = match v {
+                        Value::Number(n) => n.as_f64().ok_or_else(|| Err::out_of_range(stringify!($t)))?,
+                        v => return Err(Err::invalid_type("number", v.type_name())),
+                    };
+                    // finite numbers beyond the range of f32 would become infinite.
+                    let f = n as $t;
+                    if f.is_finite() || !n.is_finite() {
+                        Ok(f)
+                    } else {
+                        Err(Err::out_of_range(stringify!($t)))
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_json_for_floats!(f32, f64);
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        match self {
+            Some(v) => v.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<'a, T: FromJson<'a>> FromJson<'a> for Option<T> {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::Null => Ok(None),
+            v => T::from_json(v).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<'a, T: FromJson<'a>> FromJson<'a> for Box<T> {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        T::from_json(v).map(Box::new)
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<'a, T: FromJson<'a>> FromJson<'a> for Vec<T> {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, v)| T::from_json(v).map_err(|e| e.at(&i.to_string())))
+                .collect(),
+            v => Err(Err::invalid_type("array", v.type_name())),
+        }
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<'a, T: FromJson<'a>, const N: usize> FromJson<'a> for [T; N] {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        match v {
+            Value::Array(arr) if arr.len() != N => Err(Err::invalid_length(N, arr.len())),
+            v => Vec::<T>::from_json(v).map(|vec| match <[T; N]>::try_from(vec) {
+                Ok(arr) => arr,
+                Err(_) => unreachable!(),
+            }),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    fn to_json(&self) -> Value {
+        Value::Object(Box::new(
+            self.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+        ))
+    }
+}
+
+impl<'a, T: FromJson<'a>> FromJson<'a> for HashMap<String, T> {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        from_members(v)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Value {
+        Value::Object(Box::new(
+            self.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+        ))
+    }
+}
+
+impl<'a, T: FromJson<'a>> FromJson<'a> for BTreeMap<String, T> {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        from_members(v)
+    }
+}
+
+fn from_members<'a, T, C>(v: &'a Value) -> Result<C, Err>
+where
+    T: FromJson<'a>,
+    C: std::iter::FromIterator<(String, T)>,
+{
+    match v {
+        Value::Object(obj) => obj
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), T::from_json(v).map_err(|e| e.at(k))?)))
+            .collect(),
+        v => Err(Err::invalid_type("object", v.type_name())),
+    }
+}
+
+// tuples are arrays of exactly their length.
+macro_rules! impl_json_for_tuples {
+    ( $( $len:expr => ( $( $i:tt $t:ident ),+ ) )* ) => {
+        $(
+            impl<$( $t: ToJson ),+> ToJson for ( $( $t, )+ ) {
+                fn to_json(&self) -> Value {
+                    Value::Array(vec![ $( self.$i.to_json() ),+ ])
+                }
+            }
+
+            impl<'a, $( $t: FromJson<'a> ),+> FromJson<'a> for ( $( $t, )+ ) {
+                fn from_json(v: &'a Value) -> Result<Self, Err> {
+                    match v {
+                        Value::Array(arr) if arr.len() == $len => Ok(( $(
+                            $t::from_json(&arr[$i]).map_err(|e| e.at(stringify!($i)))?,
+                        )+ )),
+                        Value::Array(arr) => Err(Err::invalid_length($len, arr.len())),
+                        v => Err(Err::invalid_type(
+                            concat!("array of length ", stringify!($len)),
+                            v.type_name(),
+                        )),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_json_for_tuples! {
+    1 => (0 A)
+    2 => (0 A, 1 B)
+    3 => (0 A, 1 B, 2 C)
+    4 => (0 A, 1 B, 2 C, 3 D)
+    5 => (0 A, 1 B, 2 C, 3 D, 4 E)
+    6 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F)
+}
@@ -1,9 +1,178 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::pointer::escape_token;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Err {
+    kind: ErrKind,
+    line: Option<usize>,
+    column: Option<usize>,
+    path: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
-pub struct Err {} // TODO
+pub enum ErrKind {
+    Syntax,
+    // message of the underlying io::Error
+    Io(String),
+    // a value of another JSON type than the Rust type converted to.
+    Type {
+        expected: &'static str,
+        found: &'static str,
+    },
+    // an array of another length than the Rust array converted to.
+    Length {
+        expected: usize,
+        found: usize,
+    },
+    // a member of an object which is required for the conversion.
+    MissingField(String),
+    // a member of an object which the Rust type has no field for.
+    UnknownField(String),
+    // a tag which names none of the variants of an enum.
+    UnknownVariant(String),
+    // a value which none of the variants of an untagged enum reads.
+    NoMatchingVariant(&'static str),
+    // a number which doesn't fit into the Rust type converted to.
+    OutOfRange(&'static str),
+    // a binary-only type, such as a CBOR byte string, with no JSON equivalent.
+    Unsupported(&'static str),
+    // the limits set in `Limits`.
+    DepthLimitExceeded,
+    InputTooLarge,
+    StringTooLong,
+    TooManyMembers,
+}
 
 impl Err {
-    // TODO: embed error info: cause, line, position, ...
+    // TODO: embed error info: cause, position, ...
     pub fn new() -> Self {
-        Err {}
+        Self::from_kind(ErrKind::Syntax)
+    }
+
+    pub fn from_kind(kind: ErrKind) -> Self {
+        Err {
+            kind,
+            line: None,
+            column: None,
+            path: None,
+        }
+    }
+
+    pub fn io(e: &io::Error) -> Self {
+        Self::from_kind(ErrKind::Io(e.to_string()))
+    }
+
+    pub fn invalid_type(expected: &'static str, found: &'static str) -> Self {
+        Self::from_kind(ErrKind::Type { expected, found })
+    }
+
+    pub fn invalid_length(expected: usize, found: usize) -> Self {
+        Self::from_kind(ErrKind::Length { expected, found })
+    }
+
+    pub fn missing_field(key: &str) -> Self {
+        Self::from_kind(ErrKind::MissingField(key.to_string()))
+    }
+
+    pub fn unknown_field(key: &str) -> Self {
+        Self::from_kind(ErrKind::UnknownField(key.to_string()))
+    }
+
+    pub fn unknown_variant(tag: &str) -> Self {
+        Self::from_kind(ErrKind::UnknownVariant(tag.to_string()))
+    }
+
+    pub fn no_matching_variant(ty: &'static str) -> Self {
+        Self::from_kind(ErrKind::NoMatchingVariant(ty))
+    }
+
+    pub fn out_of_range(ty: &'static str) -> Self {
+        Self::from_kind(ErrKind::OutOfRange(ty))
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    // 1-based line and column of the error in the parsed text.
+    pub fn with_position(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    // prefixes the JSON pointer of the failed value with the key or index it was found at.
+    pub fn at(mut self, token: &str) -> Self {
+        let parent = format!("/{}", escape_token(token));
+        self.path = Some(parent + self.path.as_deref().unwrap_or(""));
+        self
+    }
+
+    pub fn kind(&self) -> &ErrKind {
+        &self.kind
+    }
+
+    // 1-based line number, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    // 1-based column number in characters, if known.
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    // JSON pointer of the value which failed to convert, if known.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
     }
 }
+
+impl Default for Err {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Err {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrKind::Syntax => f.write_str("syntax error")?,
+            ErrKind::Io(msg) => write!(f, "io error: {}", msg)?,
+            ErrKind::Type { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)?
+            }
+            ErrKind::Length { expected, found } => write!(
+                f,
+                "expected array of length {}, found array of length {}",
+                expected, found
+            )?,
+            ErrKind::MissingField(key) => write!(f, "missing field `{}`", key)?,
+            ErrKind::UnknownField(key) => write!(f, "unknown field `{}`", key)?,
+            ErrKind::UnknownVariant(tag) => write!(f, "unknown variant `{}`", tag)?,
+            ErrKind::NoMatchingVariant(ty) => write!(f, "no variant of {} matches", ty)?,
+            ErrKind::OutOfRange(ty) => write!(f, "number out of range for {}", ty)?,
+            ErrKind::Unsupported(ty) => write!(f, "{} has no JSON equivalent", ty)?,
+            ErrKind::DepthLimitExceeded => f.write_str("nesting too deep")?,
+            ErrKind::InputTooLarge => f.write_str("input too large")?,
+            ErrKind::StringTooLong => f.write_str("string too long")?,
+            ErrKind::TooManyMembers => f.write_str("too many object members")?,
+        }
+        if let Some(path) = &self.path {
+            write!(f, " at {}", path)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, " at line {}", line)?;
+            if let Some(column) = self.column {
+                write!(f, " column {}", column)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Err {}
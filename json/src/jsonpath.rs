@@ -0,0 +1,525 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use super::error::{Err, ErrKind};
+use super::number::Number;
+use super::value::Value;
+
+// JSONPath query such as `$.items[?(@.status=="ok")].id`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(CompareOp, Operand, Operand),
+    Exists(Operand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Literal(Value),
+    // `$...` or `@...`
+    Path(bool, JsonPath),
+}
+
+impl JsonPath {
+    pub fn parse(s: &str) -> Result<Self, Err> {
+        let mut p = PathParser {
+            s: s.as_bytes(),
+            pos: 0,
+            depth: 0,
+        };
+        if p.next() != Some(b'$') {
+            return Err(Err::new());
+        }
+        let path = p.parse_segments()?;
+        if p.cur().is_some() {
+            return Err(Err::new());
+        }
+        Ok(path)
+    }
+
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        self.select_from(root, root)
+    }
+
+    fn select_from<'a>(&self, root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+        let mut nodes = vec![current];
+        for segment in &self.segments {
+            let mut selected = Vec::new();
+            for node in nodes {
+                match segment {
+                    Segment::Child(selectors) => {
+                        select_children(root, node, selectors, &mut selected)
+                    }
+                    Segment::Descendant(selectors) => {
+                        let mut nodes = Vec::new();
+                        descendants(node, &mut nodes);
+                        for node in nodes {
+                            select_children(root, node, selectors, &mut selected);
+                        }
+                    }
+                }
+            }
+            nodes = selected;
+        }
+        nodes
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JsonPath::parse(s)
+    }
+}
+
+impl Value {
+    pub fn query(&self, path: &str) -> Result<Vec<&Value>, Err> {
+        Ok(JsonPath::parse(path)?.select(self))
+    }
+}
+
+// the node itself and all of its descendants in document order.
+fn descendants<'a>(node: &'a Value, nodes: &mut Vec<&'a Value>) {
+    nodes.push(node);
+    match node {
+        Value::Array(arr) => arr.iter().for_each(|v| descendants(v, nodes)),
+        Value::Object(obj) => obj.values().for_each(|v| descendants(v, nodes)),
+        _ => (),
+    }
+}
+
+fn select_children<'a>(
+    root: &'a Value,
+    node: &'a Value,
+    selectors: &[Selector],
+    selected: &mut Vec<&'a Value>,
+) {
+    for selector in selectors {
+        match (selector, node) {
+            (Selector::Name(name), Value::Object(obj)) => selected.extend(obj.get(name)),
+            (Selector::Index(i), Value::Array(arr)) => {
+                let i = if *i < 0 { arr.len() as i64 + i } else { *i };
+                if i >= 0 {
+                    selected.extend(arr.get(i as usize));
+                }
+            }
+            (Selector::Wildcard, Value::Array(arr)) => selected.extend(arr.iter()),
+            (Selector::Wildcard, Value::Object(obj)) => selected.extend(obj.values()),
+            (Selector::Slice(start, end, step), Value::Array(arr)) => {
+                for i in slice_indices(arr.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    selected.push(&arr[i]);
+                }
+            }
+            (Selector::Filter(expr), Value::Array(arr)) => {
+                selected.extend(arr.iter().filter(|v| expr.test(root, v)))
+            }
+            (Selector::Filter(expr), Value::Object(obj)) => {
+                selected.extend(obj.values().filter(|v| expr.test(root, v)))
+            }
+            _ => (),
+        }
+    }
+}
+
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).max(0).min(len);
+        let upper = normalize(end.unwrap_or(len)).max(0).min(len);
+        let mut i = Some(lower);
+        while let Some(j) = i.filter(|&j| j < upper) {
+            indices.push(j as usize);
+            i = j.checked_add(step);
+        }
+    } else if step < 0 {
+        let upper = start.map_or(len - 1, normalize).max(-1).min(len - 1);
+        let lower = end.map_or(-1, normalize).max(-1).min(len - 1);
+        let mut i = Some(upper);
+        while let Some(j) = i.filter(|&j| j > lower) {
+            indices.push(j as usize);
+            i = j.checked_add(step);
+        }
+    }
+    indices
+}
+
+impl Expr {
+    fn test(&self, root: &Value, current: &Value) -> bool {
+        match self {
+            Expr::Or(l, r) => l.test(root, current) || r.test(root, current),
+            Expr::And(l, r) => l.test(root, current) && r.test(root, current),
+            Expr::Not(e) => !e.test(root, current),
+            Expr::Exists(operand) => match operand {
+                Operand::Path(absolute, path) => {
+                    let from = if *absolute { root } else { current };
+                    !path.select_from(root, from).is_empty()
+                }
+                Operand::Literal(_) => false,
+            },
+            Expr::Compare(op, l, r) => {
+                let l = l.eval(root, current);
+                let r = r.eval(root, current);
+                match op {
+                    CompareOp::Eq => l == r,
+                    CompareOp::Ne => l != r,
+                    CompareOp::Lt => compare(l, r) == Some(Ordering::Less),
+                    CompareOp::Le => l == r || compare(l, r) == Some(Ordering::Less),
+                    CompareOp::Gt => compare(l, r) == Some(Ordering::Greater),
+                    CompareOp::Ge => l == r || compare(l, r) == Some(Ordering::Greater),
+                }
+            }
+        }
+    }
+}
+
+impl Operand {
+    // evaluates to the value only when the path selects exactly one node.
+    fn eval<'a>(&'a self, root: &'a Value, current: &'a Value) -> Option<&'a Value> {
+        match self {
+            Operand::Literal(v) => Some(v),
+            Operand::Path(absolute, path) => {
+                let from = if *absolute { root } else { current };
+                let nodes = path.select_from(root, from);
+                if nodes.len() == 1 {
+                    Some(nodes[0])
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+fn compare(l: Option<&Value>, r: Option<&Value>) -> Option<Ordering> {
+    match (l?, r?) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+// nested `!`, `(` and filters. expressions are parsed and evaluated recursively, so
+// deeper ones are rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+struct PathParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> PathParser<'a> {
+    fn parse_segments(&mut self) -> Result<JsonPath, Err> {
+        let mut segments = Vec::new();
+        loop {
+            match (self.cur(), self.peek(1)) {
+                (Some(b'.'), Some(b'.')) => {
+                    self.pos += 2;
+                    let selectors = match self.cur() {
+                        Some(b'[') => self.parse_bracket()?,
+                        _ => vec![self.parse_dot_selector()?],
+                    };
+                    segments.push(Segment::Descendant(selectors));
+                }
+                (Some(b'.'), _) => {
+                    self.pos += 1;
+                    segments.push(Segment::Child(vec![self.parse_dot_selector()?]));
+                }
+                (Some(b'['), _) => segments.push(Segment::Child(self.parse_bracket()?)),
+                _ => break,
+            }
+        }
+        Ok(JsonPath { segments })
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Selector, Err> {
+        if self.cur() == Some(b'*') {
+            self.pos += 1;
+            return Ok(Selector::Wildcard);
+        }
+        let start = self.pos;
+        while let Some(b) = self.cur() {
+            if b.is_ascii_alphanumeric() || b == b'_' || b == b'-' || b >= 0x80 {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        if start == self.pos {
+            return Err(Err::new());
+        }
+        Ok(Selector::Name(self.str_from(start)?))
+    }
+
+    fn parse_bracket(&mut self) -> Result<Vec<Selector>, Err> {
+        self.expect(b'[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_spaces();
+            selectors.push(self.parse_bracket_selector()?);
+            self.skip_spaces();
+            match self.next() {
+                Some(b',') => (),
+                Some(b']') => return Ok(selectors),
+                _ => return Err(Err::new()),
+            }
+        }
+    }
+
+    fn parse_bracket_selector(&mut self) -> Result<Selector, Err> {
+        match self.cur() {
+            Some(b'\'') | Some(b'"') => Ok(Selector::Name(self.parse_quoted()?)),
+            Some(b'*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some(b'?') => {
+                self.pos += 1;
+                self.skip_spaces();
+                Ok(Selector::Filter(self.nested(Self::parse_or)?))
+            }
+            _ => {
+                let start = self.parse_int()?;
+                self.skip_spaces();
+                if self.cur() != Some(b':') {
+                    return start.map(Selector::Index).ok_or_else(Err::new);
+                }
+                self.pos += 1;
+                self.skip_spaces();
+                let end = self.parse_int()?;
+                self.skip_spaces();
+                let step = if self.cur() == Some(b':') {
+                    self.pos += 1;
+                    self.skip_spaces();
+                    self.parse_int()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice(start, end, step))
+            }
+        }
+    }
+
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T, Err>) -> Result<T, Err> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Err::from_kind(ErrKind::DepthLimitExceeded));
+        }
+        self.depth += 1;
+        let res = parse(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, Err> {
+        let mut expr = self.parse_and()?;
+        while self.eat(b"||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, Err> {
+        let mut expr = self.parse_unary()?;
+        while self.eat(b"&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, Err> {
+        self.skip_spaces();
+        if self.cur() == Some(b'!') && self.peek(1) != Some(b'=') {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.nested(Self::parse_unary)?)));
+        }
+        if self.eat(b"(") {
+            let expr = self.nested(Self::parse_or)?;
+            if !self.eat(b")") {
+                return Err(Err::new());
+            }
+            return Ok(expr);
+        }
+
+        let l = self.parse_operand()?;
+        let op = if self.eat(b"==") {
+            CompareOp::Eq
+        } else if self.eat(b"!=") {
+            CompareOp::Ne
+        } else if self.eat(b"<=") {
+            CompareOp::Le
+        } else if self.eat(b">=") {
+            CompareOp::Ge
+        } else if self.eat(b"<") {
+            CompareOp::Lt
+        } else if self.eat(b">") {
+            CompareOp::Gt
+        } else {
+            return match l {
+                Operand::Path(_, _) => Ok(Expr::Exists(l)),
+                Operand::Literal(_) => Err(Err::new()),
+            };
+        };
+        Ok(Expr::Compare(op, l, self.parse_operand()?))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, Err> {
+        self.skip_spaces();
+        let operand = match self.cur() {
+            Some(b'@') | Some(b'$') => {
+                let absolute = self.next() == Some(b'$');
+                Operand::Path(absolute, self.parse_segments()?)
+            }
+            Some(b'\'') | Some(b'"') => Operand::Literal(Value::String(self.parse_quoted()?)),
+            Some(b'-') | Some(b'0'..=b'9') => Operand::Literal(self.parse_number()?),
+            _ => {
+                if self.eat(b"true") {
+                    Operand::Literal(Value::Boolean(true))
+                } else if self.eat(b"false") {
+                    Operand::Literal(Value::Boolean(false))
+                } else if self.eat(b"null") {
+                    Operand::Literal(Value::Null)
+                } else {
+                    return Err(Err::new());
+                }
+            }
+        };
+        self.skip_spaces();
+        Ok(operand)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, Err> {
+        let start = self.pos;
+        while let Some(b) = self.cur() {
+            match b {
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => self.pos += 1,
+                _ => break,
+            }
+        }
+        let s = self.str_from(start)?;
+        match s.parse::<f64>() {
+            Ok(_) => Number::from_json_str(&s)
+                .map(Value::Number)
+                .ok_or_else(Err::new),
+            Err(_) => Err(Err::new()),
+        }
+    }
+
+    fn parse_int(&mut self) -> Result<Option<i64>, Err> {
+        let start = self.pos;
+        if self.cur() == Some(b'-') {
+            self.pos += 1;
+        }
+        while let Some(b'0'..=b'9') = self.cur() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        self.str_from(start)?
+            .parse()
+            .map(Some)
+            .map_err(|_| Err::new())
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, Err> {
+        let quote = self.next();
+        let mut buf = Vec::new();
+        loop {
+            match self.next() {
+                None => return Err(Err::new()),
+                Some(b) if Some(b) == quote => break,
+                Some(b'\\') => match self.next() {
+                    Some(b'b') => buf.push(b'\x08'),
+                    Some(b'f') => buf.push(b'\x0C'),
+                    Some(b'n') => buf.push(b'\n'),
+                    Some(b'r') => buf.push(b'\r'),
+                    Some(b't') => buf.push(b'\t'),
+                    Some(b @ b'\'') | Some(b @ b'"') | Some(b @ b'\\') | Some(b @ b'/') => {
+                        buf.push(b)
+                    }
+                    _ => return Err(Err::new()),
+                },
+                Some(b) => buf.push(b),
+            }
+        }
+        String::from_utf8(buf).map_err(|_| Err::new())
+    }
+
+    fn eat(&mut self, bs: &[u8]) -> bool {
+        self.skip_spaces();
+        if self.s[self.pos..].starts_with(bs) {
+            self.pos += bs.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Err> {
+        if self.next() == Some(b) {
+            Ok(())
+        } else {
+            Err(Err::new())
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.cur() {
+            self.pos += 1;
+        }
+    }
+
+    fn str_from(&self, start: usize) -> Result<String, Err> {
+        String::from_utf8(self.s[start..self.pos].to_vec()).map_err(|_| Err::new())
+    }
+
+    fn cur(&self) -> Option<u8> {
+        self.peek(0)
+    }
+
+    fn peek(&self, n: usize) -> Option<u8> {
+        self.s.get(self.pos + n).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.cur();
+        if b.is_some() {
+            self.pos += 1;
+        }
+        b
+    }
+}
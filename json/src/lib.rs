@@ -1,11 +1,31 @@
+pub mod canonical;
+pub mod cbor;
+mod convert;
 mod error;
+mod jsonpath;
 mod macros;
+pub mod msgpack;
+pub mod ndjson;
+mod number;
 mod parser;
+pub mod patch;
+mod pointer;
 mod reader;
+// public only for the benchmarks.
+#[doc(hidden)]
+pub mod scan;
+pub mod schema;
+mod serializer;
 mod value;
+mod value_ref;
 
-pub use error::Err;
+pub use convert::{FromJson, FromJsonOwned, ToJson};
+pub use error::{Err, ErrKind};
+pub use jsonpath::JsonPath;
+pub use number::Number;
+pub use parser::{Limits, ParserOptions};
 pub use value::Value;
+pub use value_ref::ValueRef;
 
 use parser::Parser;
 use reader::BytesReader;
@@ -15,6 +35,27 @@ impl FromStr for Value {
     type Err = error::Err;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Parser::new(BytesReader::new(s.as_bytes())).parse()
+        from_slice(s.as_bytes())
     }
 }
+
+pub fn from_slice(bs: &[u8]) -> Result<Value, Err> {
+    Parser::new(BytesReader::new(bs)).parse()
+}
+
+pub fn from_slice_with_options(bs: &[u8], options: ParserOptions) -> Result<Value, Err> {
+    Parser::with_options(BytesReader::new(bs), options).parse()
+}
+
+pub fn from_str_with_options(s: &str, options: ParserOptions) -> Result<Value, Err> {
+    from_slice_with_options(s.as_bytes(), options)
+}
+
+// parses without copying strings which have no escape sequences.
+pub fn from_slice_borrowed<'a>(bs: &'a [u8]) -> Result<ValueRef<'a>, Err> {
+    Parser::new(BytesReader::new(bs)).parse_borrowed()
+}
+
+pub fn from_str_borrowed<'a>(s: &'a str) -> Result<ValueRef<'a>, Err> {
+    from_slice_borrowed(s.as_bytes())
+}
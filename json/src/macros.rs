@@ -1,33 +1,139 @@
 #[macro_export]
 macro_rules! value {
+    // arrays are built by munching one element at a time into `@array [elems,] rest`.
+    (@array [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        ::std::vec![$($elems),*]
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!(true)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!(false)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($arr:tt)*] $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!([$($arr)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($obj:tt)*} $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!({$($obj)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::value!(@array [$($elems,)* $crate::value!($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)*] $($rest)*)
+    };
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::__value_unexpected!($unexpected)
+    };
+
+    // objects are built by munching key tokens until `:`, then the value, into `$obj`.
+    // the rest of the tokens is passed twice so that errors can point at the offending one.
+    (@object $obj:ident () () ()) => {};
+    (@object $obj:ident [$($key:tt)+] ($val:expr) , $($rest:tt)*) => {
+        let _ = $obj.insert(::std::string::ToString::to_string(&($($key)+)), $val);
+        $crate::value!(@object $obj () ($($rest)*) ($($rest)*));
+    };
+    (@object $obj:ident [$($key:tt)+] ($val:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::__value_unexpected!($unexpected);
+    };
+    (@object $obj:ident [$($key:tt)+] ($val:expr)) => {
+        let _ = $obj.insert(::std::string::ToString::to_string(&($($key)+)), $val);
+    };
+    (@object $obj:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!(null)) $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!(true)) $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!(false)) $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: [$($arr:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!([$($arr)*])) $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!({$($inner)*})) $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: $val:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!($val)) , $($rest)*);
+    };
+    (@object $obj:ident ($($key:tt)+) (: $val:expr) $copy:tt) => {
+        $crate::value!(@object $obj [$($key)+] ($crate::value!($val)));
+    };
+    // a key without a value.
+    (@object $obj:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::value!();
+    };
+    (@object $obj:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::value!();
+    };
+    (@object $obj:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::__value_unexpected!($colon);
+    };
+    (@object $obj:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::__value_unexpected!($comma);
+    };
+    (@object $obj:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj ($key) (: $($rest)*) (: $($rest)*));
+    };
+    (@object $obj:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::value!(@object $obj ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
     (null) => {
         $crate::Value::Null
     };
-    ([ $($val:tt),* ]) => {
-        {
-            let mut arr = Vec::new();
-            $(
-                arr.push($crate::value!($val));
-            )*
-            $crate::Value::Array(arr)
-        }
+    (true) => {
+        $crate::Value::Boolean(true)
+    };
+    (false) => {
+        $crate::Value::Boolean(false)
+    };
+    ([]) => {
+        $crate::Value::Array(::std::vec::Vec::new())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Array($crate::value!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::Value::Object(::std::boxed::Box::default())
     };
-    ({ $($key:tt : $val:tt),* }) => {
-        {
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Object(::std::boxed::Box::new({
             let mut obj = ::std::collections::HashMap::new();
-            $(
-                obj.insert(::std::string::ToString::to_string($key), $crate::value!($val));
-            )*
-            $crate::Value::Object(Box::new(obj))
-        }
+            $crate::value!(@object obj () ($($tt)+) ($($tt)+));
+            obj
+        }))
     };
     ($val:expr) => {
         $crate::Value::from($val)
     };
 }
 
+// fails the expansion with the unexpected token in the error message.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __value_unexpected {
+    () => {};
+}
+
+use super::convert::FromJson;
+use super::error::Err;
+use super::number::Number;
 use super::value::Value;
-use std::convert::From;
+use std::collections::HashMap;
+use std::convert::{From, TryFrom};
+use std::iter::FromIterator;
+use std::mem;
 
 impl From<bool> for Value {
     fn from(v: bool) -> Value {
@@ -49,10 +155,83 @@ macro_rules! impl_from_numbers_for_value {
         $(
             impl From<$t> for Value {
                 fn from(v: $t) -> Value {
-                    Value::Number(v as f64)
+                    Value::Number(Number::from(v))
+                }
+            }
+        )*
+    };
+}
+impl_from_numbers_for_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+impl From<Number> for Value {
+    fn from(v: Number) -> Value {
+        Value::Number(v)
+    }
+}
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Value {
+        match v {
+            Some(v) => v.into(),
+            None => Value::Null,
+        }
+    }
+}
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Value {
+        Value::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+impl<T: Into<Value>> From<HashMap<String, T>> for Value {
+    fn from(v: HashMap<String, T>) -> Value {
+        Value::Object(Box::new(
+            v.into_iter().map(|(k, v)| (k, v.into())).collect(),
+        ))
+    }
+}
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Value {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+impl<T: Into<Value>> FromIterator<(String, T)> for Value {
+    fn from_iter<I: IntoIterator<Item = (String, T)>>(iter: I) -> Value {
+        Value::Object(Box::new(
+            iter.into_iter().map(|(k, v)| (k, v.into())).collect(),
+        ))
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Err;
+
+    fn try_from(v: Value) -> Result<Self, Err> {
+        match v {
+            Value::Boolean(b) => Ok(b),
+            v => Err(Err::invalid_type("boolean", v.type_name())),
+        }
+    }
+}
+impl TryFrom<Value> for String {
+    type Error = Err;
+
+    fn try_from(mut v: Value) -> Result<Self, Err> {
+        match &mut v {
+            Value::String(s) => Ok(mem::take(s)),
+            v => Err(Err::invalid_type("string", v.type_name())),
+        }
+    }
+}
+// numbers convert the same way as with `FromJson`, so both fail with the same errors.
+macro_rules! impl_try_from_value_for_numbers {
+    ( $( $t:ident ),* ) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = Err;
+
+                fn try_from(v: Value) -> Result<Self, Err> {
+                    FromJson::from_json(&v)
                 }
             }
         )*
     };
 }
-impl_from_numbers_for_value!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+impl_try_from_value_for_numbers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
@@ -0,0 +1,195 @@
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use json::{ndjson, FromJson, JsonPath, ParserOptions, Value};
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+
+fn main() {
+    let files = Arg::with_name("files")
+        .multiple(true)
+        .help("Input files. Reads stdin when none is given or for `-`");
+    let app = App::new("json")
+        .about("Validates, formats and queries JSON")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("json5")
+                .long("json5")
+                .global(true)
+                .help("Accepts JSON5 input"),
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Checks that the input is valid JSON")
+                .arg(files.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("pretty")
+                .about("Prints the input indented, with sorted keys")
+                .arg(files.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("minify")
+                .about("Prints the input without whitespace")
+                .arg(files.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("Prints the values at a JSON Pointer (/a/0) or JSONPath ($.a[0])")
+                .arg(Arg::with_name("path").required(true))
+                .arg(
+                    Arg::with_name("compact")
+                        .short("c")
+                        .long("compact")
+                        .help("Prints each value on a single line"),
+                )
+                .arg(files.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("to-array")
+                .about("Converts NDJSON into a JSON array")
+                .arg(files.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("to-ndjson")
+                .about("Converts a JSON array into NDJSON")
+                .arg(files),
+        );
+
+    let m = app.get_matches();
+    let (name, sub) = m.subcommand();
+    let sub = sub.unwrap();
+    let cmd = match Command::parse(name, sub) {
+        Ok(cmd) => cmd,
+        Err(msg) => {
+            eprintln!("json: {}", msg);
+            process::exit(2);
+        }
+    };
+    let options = if m.is_present("json5") || sub.is_present("json5") {
+        ParserOptions::json5()
+    } else {
+        ParserOptions::default()
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let mut ok = true;
+    for file in input_files(sub) {
+        let result = read(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|bs| cmd.run(&bs, &options, &mut out));
+        if let Err(msg) = result {
+            // the output so far is kept in order with the error.
+            let _ = out.flush();
+            eprintln!("{}: {}", file, msg);
+            ok = false;
+        }
+    }
+    if out.flush().is_err() || !ok {
+        process::exit(1);
+    }
+}
+
+enum Command {
+    Validate,
+    Pretty,
+    Minify,
+    Query(Path, bool),
+    ToArray,
+    ToNdjson,
+}
+
+enum Path {
+    Pointer(String),
+    JsonPath(JsonPath),
+}
+
+impl Command {
+    fn parse(name: &str, m: &ArgMatches) -> Result<Self, String> {
+        Ok(match name {
+            "validate" => Command::Validate,
+            "pretty" => Command::Pretty,
+            "minify" => Command::Minify,
+            "query" => {
+                let path = m.value_of("path").unwrap();
+                let path = if path.starts_with('$') {
+                    let p = path
+                        .parse()
+                        .map_err(|_| format!("invalid path: {}", path))?;
+                    Path::JsonPath(p)
+                } else if path.is_empty() || path.starts_with('/') {
+                    Path::Pointer(path.to_string())
+                } else {
+                    return Err(format!("invalid path: {}", path));
+                };
+                Command::Query(path, m.is_present("compact"))
+            }
+            "to-array" => Command::ToArray,
+            "to-ndjson" => Command::ToNdjson,
+            _ => unreachable!(),
+        })
+    }
+
+    // errors are returned as messages, to be printed after the file name.
+    fn run<W: Write>(&self, bs: &[u8], options: &ParserOptions, w: &mut W) -> Result<(), String> {
+        let parse =
+            || json::from_slice_with_options(bs, options.clone()).map_err(|e| e.to_string());
+        match self {
+            Command::Validate => {
+                parse()?;
+            }
+            Command::Pretty => write(w, &parse()?.to_string_pretty())?,
+            Command::Minify => write(w, &parse()?.to_string())?,
+            Command::Query(path, compact) => {
+                let v = parse()?;
+                let found = match path {
+                    Path::Pointer(p) => v.pointer(p).into_iter().collect(),
+                    Path::JsonPath(p) => p.select(&v),
+                };
+                for v in found {
+                    if *compact {
+                        write(w, &v.to_string())?;
+                    } else {
+                        write(w, &v.to_string_pretty())?;
+                    }
+                }
+            }
+            Command::ToArray => {
+                let arr = ndjson::Reader::new(bs)
+                    .options(options.clone())
+                    .collect::<Result<Vec<Value>, _>>()
+                    .map_err(|e| e.to_string())?;
+                write(w, &Value::Array(arr).to_string())?;
+            }
+            Command::ToNdjson => {
+                let arr = Vec::<Value>::from_json(&parse()?).map_err(|e| e.to_string())?;
+                let mut writer = ndjson::Writer::new(w);
+                for v in arr.iter() {
+                    writer.write(v).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn input_files(m: &ArgMatches) -> Vec<String> {
+    match m.values_of("files") {
+        Some(files) => files.map(String::from).collect(),
+        None => vec!["-".to_string()],
+    }
+}
+
+fn read(file: &str) -> io::Result<Vec<u8>> {
+    if file == "-" {
+        let mut buf = Vec::new();
+        io::stdin().read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        fs::read(file)
+    }
+}
+
+fn write<W: Write>(w: &mut W, s: &str) -> Result<(), String> {
+    writeln!(w, "{}", s).map_err(|e| e.to_string())
+}
@@ -0,0 +1,308 @@
+// MessagePack encoding of values. binary and extension types have no JSON equivalent
+// and fail to decode, as do maps with non-string keys.
+use std::collections::HashMap;
+use std::mem;
+use std::str;
+
+use super::error::{Err, ErrKind};
+use super::number::Number;
+use super::parser::Limits;
+use super::reader::{BytesReader, Reader};
+use super::value::Value;
+
+pub fn to_vec(v: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_value(&mut buf, v);
+    buf
+}
+
+pub fn from_slice(bs: &[u8]) -> Result<Value, Err> {
+    from_slice_with_limits(bs, Limits::default())
+}
+
+// decodes with the same limits as text JSON.
+pub fn from_slice_with_limits(bs: &[u8], limits: Limits) -> Result<Value, Err> {
+    if bs.len() > limits.max_input_size {
+        return Err(Err::from_kind(ErrKind::InputTooLarge));
+    }
+    let mut d = Decoder {
+        r: BytesReader::new(bs),
+        limits,
+    };
+    let v = d.decode()?;
+    match d.r.cur() {
+        None => Ok(v),
+        Some(_) => Err(Err::new()),
+    }
+}
+
+fn write_value(buf: &mut Vec<u8>, v: &Value) {
+    match v {
+        Value::Null => buf.push(0xC0),
+        Value::Boolean(false) => buf.push(0xC2),
+        Value::Boolean(true) => buf.push(0xC3),
+        Value::Number(n) => write_number(buf, n),
+        Value::String(s) => write_str(buf, s),
+        Value::Array(arr) => {
+            write_len(buf, arr.len(), 0x90, 0xDC);
+            for v in arr {
+                write_value(buf, v);
+            }
+        }
+        Value::Object(obj) => {
+            write_len(buf, obj.len(), 0x80, 0xDE);
+            for (k, v) in obj.iter() {
+                write_str(buf, k);
+                write_value(buf, v);
+            }
+        }
+    }
+}
+
+fn write_number(buf: &mut Vec<u8>, n: &Number) {
+    if let Some(u) = n.as_u64() {
+        match u {
+            0..=0x7F => buf.push(u as u8),
+            0x80..=0xFF => buf.extend_from_slice(&[0xCC, u as u8]),
+            0x100..=0xFFFF => {
+                buf.push(0xCD);
+                buf.extend_from_slice(&(u as u16).to_be_bytes());
+            }
+            0x1_0000..=0xFFFF_FFFF => {
+                buf.push(0xCE);
+                buf.extend_from_slice(&(u as u32).to_be_bytes());
+            }
+            _ => {
+                buf.push(0xCF);
+                buf.extend_from_slice(&u.to_be_bytes());
+            }
+        }
+    } else if let Some(i) = n.as_i64() {
+        // only negative numbers are left here.
+        if i >= -32 {
+            buf.push(i as u8);
+        } else if i >= i8::MIN as i64 {
+            buf.extend_from_slice(&[0xD0, i as u8]);
+        } else if i >= i16::MIN as i64 {
+            buf.push(0xD1);
+            buf.extend_from_slice(&(i as i16).to_be_bytes());
+        } else if i >= i32::MIN as i64 {
+            buf.push(0xD2);
+            buf.extend_from_slice(&(i as i32).to_be_bytes());
+        } else {
+            buf.push(0xD3);
+            buf.extend_from_slice(&i.to_be_bytes());
+        }
+    } else {
+        // other numbers, including arbitrary precision ones, are written as floats.
+        let f = n.as_f64().unwrap_or(f64::NAN);
+        if f as f32 as f64 == f || f.is_nan() {
+            buf.push(0xCA);
+            buf.extend_from_slice(&(f as f32).to_be_bytes());
+        } else {
+            buf.push(0xCB);
+            buf.extend_from_slice(&f.to_be_bytes());
+        }
+    }
+}
+
+fn write_str(buf: &mut Vec<u8>, s: &str) {
+    let len = s.len();
+    if len < 32 {
+        buf.push(0xA0 | len as u8);
+    } else if len <= u8::MAX as usize {
+        buf.extend_from_slice(&[0xD9, len as u8]);
+    } else if len <= u16::MAX as usize {
+        buf.push(0xDA);
+        buf.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        buf.push(0xDB);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    buf.extend_from_slice(s.as_bytes());
+}
+
+// writes the length of an array or map. `marker16` is followed by the 32 bit marker.
+fn write_len(buf: &mut Vec<u8>, len: usize, fix: u8, marker16: u8) {
+    if len < 16 {
+        buf.push(fix | len as u8);
+    } else if len <= u16::MAX as usize {
+        buf.push(marker16);
+        buf.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        buf.push(marker16 + 1);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    r: BytesReader<'a>,
+    limits: Limits,
+}
+
+// an array or map being decoded, with the number of items left.
+enum Frame {
+    Array(Vec<Value>, usize),
+    // members so far, the members left, and the key of the member being decoded.
+    Map(HashMap<String, Value>, usize, String),
+}
+
+impl<'a> Decoder<'a> {
+    // arrays and maps are kept on a stack rather than decoded recursively, as in the parser.
+    fn decode(&mut self) -> Result<Value, Err> {
+        let mut stack = Vec::new();
+        loop {
+            let mut done = self.item(&mut stack)?;
+            loop {
+                if let Some(v) = done.take() {
+                    match stack.last_mut() {
+                        None => return Ok(v),
+                        Some(Frame::Array(arr, _)) => arr.push(v),
+                        Some(Frame::Map(obj, _, key)) => {
+                            obj.insert(mem::take(key), v);
+                        }
+                    }
+                }
+                match self.next(&mut stack)? {
+                    Some(v) => done = Some(v),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    // the next item, or None when it opens an array or map.
+    fn item(&mut self, stack: &mut Vec<Frame>) -> Result<Option<Value>, Err> {
+        let b = self.byte()?;
+        let frame = match b {
+            0x80..=0x8F => self.map((b & 0x0F) as usize)?,
+            0x90..=0x9F => Frame::Array(Vec::new(), (b & 0x0F) as usize),
+            0xDC | 0xDD => Frame::Array(Vec::new(), self.len(if b == 0xDC { 2 } else { 4 })?),
+            0xDE | 0xDF => {
+                let len = self.len(if b == 0xDE { 2 } else { 4 })?;
+                self.map(len)?
+            }
+            _ => return self.scalar(b).map(Some),
+        };
+        if stack.len() >= self.limits.max_depth {
+            return Err(Err::from_kind(ErrKind::DepthLimitExceeded));
+        }
+        stack.push(frame);
+        Ok(None)
+    }
+
+    // ends the array or map on top of the stack if it has no items left, or else reads up
+    // to its next item.
+    fn next(&mut self, stack: &mut Vec<Frame>) -> Result<Option<Value>, Err> {
+        match stack.last_mut() {
+            Some(Frame::Array(_, 0)) | Some(Frame::Map(_, 0, _)) | None => {}
+            Some(Frame::Array(_, left)) => {
+                *left -= 1;
+                return Ok(None);
+            }
+            Some(Frame::Map(_, left, key)) => {
+                *left -= 1;
+                *key = self.key()?;
+                return Ok(None);
+            }
+        }
+        Ok(match stack.pop() {
+            Some(Frame::Array(arr, _)) => Some(Value::Array(arr)),
+            Some(Frame::Map(obj, ..)) => Some(Value::Object(Box::new(obj))),
+            None => None,
+        })
+    }
+
+    fn scalar(&mut self, b: u8) -> Result<Value, Err> {
+        match b {
+            0x00..=0x7F => Ok(Value::from(b)),
+            0xA0..=0xBF => self.str((b & 0x1F) as usize).map(Value::String),
+            0xC0 => Ok(Value::Null),
+            0xC2 => Ok(Value::Boolean(false)),
+            0xC3 => Ok(Value::Boolean(true)),
+            0xC4..=0xC6 => Err(unsupported("binary")),
+            0xC7..=0xC9 | 0xD4..=0xD8 => Err(unsupported("extension type")),
+            0xCA => Ok(Value::from(f32::from_bits(self.uint(4)? as u32))),
+            0xCB => Ok(Value::from(f64::from_bits(self.uint(8)?))),
+            0xCC => Ok(Value::from(self.uint(1)?)),
+            0xCD => Ok(Value::from(self.uint(2)?)),
+            0xCE => Ok(Value::from(self.uint(4)?)),
+            0xCF => Ok(Value::from(self.uint(8)?)),
+            0xD0 => Ok(Value::from(self.uint(1)? as u8 as i8)),
+            0xD1 => Ok(Value::from(self.uint(2)? as u16 as i16)),
+            0xD2 => Ok(Value::from(self.uint(4)? as u32 as i32)),
+            0xD3 => Ok(Value::from(self.uint(8)? as i64)),
+            0xD9 => {
+                let len = self.len(1)?;
+                self.str(len).map(Value::String)
+            }
+            0xDA | 0xDB => {
+                let len = self.len(if b == 0xDA { 2 } else { 4 })?;
+                self.str(len).map(Value::String)
+            }
+            0xE0..=0xFF => Ok(Value::from(b as i8)),
+            // 0xC1 is never used.
+            _ => Err(Err::new()),
+        }
+    }
+
+    fn map(&self, len: usize) -> Result<Frame, Err> {
+        self.check_members(len)?;
+        Ok(Frame::Map(HashMap::new(), len, String::new()))
+    }
+
+    fn key(&mut self) -> Result<String, Err> {
+        let b = self.byte()?;
+        let len = match b {
+            0xA0..=0xBF => (b & 0x1F) as usize,
+            0xD9 => self.len(1)?,
+            0xDA | 0xDB => self.len(if b == 0xDA { 2 } else { 4 })?,
+            _ => return Err(unsupported("non-string map key")),
+        };
+        self.str(len)
+    }
+
+    fn str(&mut self, len: usize) -> Result<String, Err> {
+        self.check_string(len)?;
+        let bs = self.take(len)?;
+        str::from_utf8(bs).map(String::from).map_err(|_| Err::new())
+    }
+
+    fn check_string(&self, len: usize) -> Result<(), Err> {
+        if len > self.limits.max_string_length {
+            return Err(Err::from_kind(ErrKind::StringTooLong));
+        }
+        Ok(())
+    }
+
+    fn check_members(&self, len: usize) -> Result<(), Err> {
+        if len > self.limits.max_object_members {
+            return Err(Err::from_kind(ErrKind::TooManyMembers));
+        }
+        Ok(())
+    }
+
+    // length of a string, array or map in `n` bytes.
+    fn len(&mut self, n: usize) -> Result<usize, Err> {
+        Ok(self.uint(n)? as usize)
+    }
+
+    // big-endian unsigned integer in `n` bytes.
+    fn uint(&mut self, n: usize) -> Result<u64, Err> {
+        Ok(self.take(n)?.iter().fold(0, |acc, &b| acc << 8 | b as u64))
+    }
+
+    fn byte(&mut self) -> Result<u8, Err> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Err> {
+        let bs = self.r.slice(n).ok_or_else(Err::new)?;
+        self.r.mov(n);
+        Ok(bs)
+    }
+}
+
+fn unsupported(ty: &'static str) -> Err {
+    Err::from_kind(ErrKind::Unsupported(ty))
+}
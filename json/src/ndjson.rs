@@ -0,0 +1,100 @@
+use std::io;
+use std::io::BufRead;
+
+use super::error::Err;
+use super::parser::ParserOptions;
+use super::value::Value;
+
+// reads newline-delimited JSON, one value per line. blank lines are ignored.
+pub struct Reader<R: io::Read> {
+    r: io::BufReader<R>,
+    line: usize,
+    skip_invalid: bool,
+    options: ParserOptions,
+    done: bool,
+}
+
+impl<R: io::Read> Reader<R> {
+    pub fn new(r: R) -> Self {
+        Reader {
+            r: io::BufReader::new(r),
+            line: 0,
+            skip_invalid: false,
+            options: ParserOptions::default(),
+            done: false,
+        }
+    }
+
+    // skips malformed lines instead of yielding errors for them.
+    pub fn skip_invalid(mut self, skip: bool) -> Self {
+        self.skip_invalid = skip;
+        self
+    }
+
+    // parses each line with the given options, e.g. ParserOptions::json5().
+    // a record still has to fit on one line.
+    pub fn options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // line number of the last record read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<R: io::Read> Iterator for Reader<R> {
+    type Item = Result<Value, Err>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::new();
+        while !self.done {
+            buf.clear();
+            match self.r.read_until(b'\n', &mut buf) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+                    if buf.iter().all(|b| b.is_ascii_whitespace()) {
+                        continue;
+                    }
+                    match super::from_slice_with_options(&buf, self.options.clone()) {
+                        Ok(v) => return Some(Ok(v)),
+                        Err(_) if self.skip_invalid => continue,
+                        Err(e) => return Some(Err(e.with_line(self.line))),
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    // the stream can't be resumed after an I/O error.
+                    self.done = true;
+                    return Some(Err(Err::io(&e).with_line(self.line + 1)));
+                }
+            }
+        }
+        None
+    }
+}
+
+// writes values as newline-delimited JSON.
+pub struct Writer<W: io::Write> {
+    w: W,
+}
+
+impl<W: io::Write> Writer<W> {
+    pub fn new(w: W) -> Self {
+        Writer { w }
+    }
+
+    pub fn write(&mut self, v: &Value) -> io::Result<()> {
+        writeln!(self.w, "{}", v)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct Number {
+    n: N,
+}
+
+#[derive(Clone, Debug)]
+enum N {
+    PosInt(u64),
+    // always less than zero.
+    NegInt(i64),
+    Float(f64),
+    // original decimal text of a number which doesn't fit into i64/u64 exactly.
+    #[cfg(feature = "arbitrary_precision")]
+    Decimal(String),
+}
+
+impl Number {
+    // parses a string which is already validated as a JSON number.
+    pub(crate) fn from_json_str(s: &str) -> Option<Self> {
+        if !s.contains(['.', 'e', 'E']) {
+            if let Ok(v) = s.parse::<u64>() {
+                return Some(Number::from(v));
+            }
+            if let Ok(v) = s.parse::<i64>() {
+                return Some(Number::from(v));
+            }
+        }
+
+        #[cfg(feature = "arbitrary_precision")]
+        {
+            Some(Number {
+                n: N::Decimal(s.to_string()),
+            })
+        }
+        #[cfg(not(feature = "arbitrary_precision"))]
+        {
+            // numbers out of the range of f64 are rejected.
+            s.parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .map(Number::from)
+        }
+    }
+
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    pub fn is_f64(&self) -> bool {
+        match self.n {
+            N::Float(_) => true,
+            #[cfg(feature = "arbitrary_precision")]
+            N::Decimal(ref s) => s.parse::<i128>().is_err(),
+            _ => false,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::PosInt(v) if v <= i64::MAX as u64 => Some(v as i64),
+            N::NegInt(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::PosInt(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.n {
+            N::PosInt(v) => Some(v as f64),
+            N::NegInt(v) => Some(v as f64),
+            N::Float(v) => Some(v),
+            #[cfg(feature = "arbitrary_precision")]
+            N::Decimal(ref s) => s.parse().ok(),
+        }
+    }
+
+    // returns the original text of the number, if it is kept.
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_str(&self) -> Option<&str> {
+        match self.n {
+            N::Decimal(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self.n {
+            N::PosInt(v) => Some(v as i128),
+            N::NegInt(v) => Some(v as i128),
+            N::Float(v) => f64_to_i128(v),
+            #[cfg(feature = "arbitrary_precision")]
+            N::Decimal(ref s) => s.parse().ok(),
+        }
+    }
+}
+
+fn f64_to_i128(v: f64) -> Option<i128> {
+    if v.is_finite() && v.fract() == 0.0 && v.abs() < 1.7e38 {
+        Some(v as i128)
+    } else {
+        None
+    }
+}
+
+// numbers are compared by their numeric value, so `1`, `1.0` and `1e0` are equal.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.as_f64() == other.as_f64(),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.n {
+            N::PosInt(v) => write!(f, "{}", v),
+            N::NegInt(v) => write!(f, "{}", v),
+            // JSON has no representation for NaN and infinities.
+            N::Float(v) if !v.is_finite() => f.write_str("null"),
+            N::Float(v) => write!(f, "{:?}", v),
+            #[cfg(feature = "arbitrary_precision")]
+            N::Decimal(ref s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! impl_from_unsigned_for_number {
+    ( $( $t:ident ),* ) => {
+        $(
+            impl From<$t> for Number {
+                fn from(v: $t) -> Number {
+                    Number { n: N::PosInt(v as u64) }
+                }
+            }
+        )*
+    };
+}
+impl_from_unsigned_for_number!(u8, u16, u32, u64, usize);
+
+macro_rules! impl_from_signed_for_number {
+    ( $( $t:ident ),* ) => {
+        $(
+            impl From<$t> for Number {
+                fn from(v: $t) -> Number {
+                    if v < 0 {
+                        Number { n: N::NegInt(v as i64) }
+                    } else {
+                        Number { n: N::PosInt(v as u64) }
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_from_signed_for_number!(i8, i16, i32, i64, isize);
+
+impl From<f32> for Number {
+    fn from(v: f32) -> Number {
+        Number::from(v as f64)
+    }
+}
+
+impl From<f64> for Number {
+    fn from(v: f64) -> Number {
+        Number { n: N::Float(v) }
+    }
+}
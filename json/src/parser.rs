@@ -1,20 +1,187 @@
+use std::borrow::Cow;
 use std::char;
-use std::collections::HashMap;
+use std::mem;
+use std::str;
 
-use super::error::Err;
+use super::error::{Err, ErrKind};
+use super::number::Number;
 use super::reader::Reader;
+use super::scan::{self, StructuralIndex};
 use super::value::Value;
+use super::value_ref::ValueRef;
 
-pub struct Parser<R: Reader> {
+// extensions to RFC 8259. all of them are disabled by default.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParserOptions {
+    // `// ...` and `/* ... */`
+    pub comments: bool,
+    // `[1, 2,]` and `{"a": 1,}`
+    pub trailing_commas: bool,
+    // `'string'`
+    pub single_quotes: bool,
+    // `{key: 1}`
+    pub unquoted_keys: bool,
+    // `0x1F`
+    pub hex_numbers: bool,
+    // `+1`, `.5` and `5.`
+    pub relaxed_numbers: bool,
+    // `NaN`, `Infinity` and `-Infinity`, and `+Infinity` with `relaxed_numbers`
+    pub special_floats: bool,
+    // a backslash before a line break in a string, which leaves both out.
+    pub line_continuations: bool,
+    pub limits: Limits,
+}
+
+// caps on the resources a document may use. exceeding one fails with its own ErrKind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Limits {
+    // nested arrays and objects. neither the parser and the binary decoders nor dropping,
+    // cloning, comparing and printing a `Value` recurse, so a large limit is safe with them.
+    // other walks over a value do, like the binary encoders, Debug, or cloning, comparing
+    // and converting a `ValueRef`, and may overflow the stack some thousands deep.
+    pub max_depth: usize,
+    // bytes of the whole input.
+    pub max_input_size: usize,
+    // bytes of a string or key after unescaping.
+    pub max_string_length: usize,
+    // members of a single object, counting duplicated keys.
+    pub max_object_members: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 128,
+            max_input_size: usize::MAX,
+            max_string_length: usize::MAX,
+            max_object_members: usize::MAX,
+        }
+    }
+}
+
+impl ParserOptions {
+    // JSON5 except for the escapes `\v`, `\0` and `\xHH`, identity escapes like `\a`,
+    // escapes in unquoted keys and whitespace outside ASCII.
+    pub fn json5() -> Self {
+        ParserOptions {
+            comments: true,
+            trailing_commas: true,
+            single_quotes: true,
+            unquoted_keys: true,
+            hex_numbers: true,
+            relaxed_numbers: true,
+            special_floats: true,
+            line_continuations: true,
+            limits: Limits::default(),
+        }
+    }
+}
+
+// builds a tree from the parsed parts. strings are borrowed from the input when they have no escapes.
+pub(crate) trait Tree<'a>: Sized {
+    fn null() -> Self;
+    fn boolean(b: bool) -> Self;
+    fn number(n: Number) -> Self;
+    fn string(s: Cow<'a, str>) -> Self;
+    fn array(arr: Vec<Self>) -> Self;
+    fn object(members: Vec<(Cow<'a, str>, Self)>) -> Self;
+}
+
+impl<'a> Tree<'a> for Value {
+    fn null() -> Self {
+        Value::Null
+    }
+
+    fn boolean(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+
+    fn number(n: Number) -> Self {
+        Value::Number(n)
+    }
+
+    fn string(s: Cow<'a, str>) -> Self {
+        Value::String(s.into_owned())
+    }
+
+    fn array(arr: Vec<Self>) -> Self {
+        Value::Array(arr)
+    }
+
+    fn object(members: Vec<(Cow<'a, str>, Self)>) -> Self {
+        Value::Object(Box::new(
+            members
+                .into_iter()
+                .map(|(k, v)| (k.into_owned(), v))
+                .collect(),
+        ))
+    }
+}
+
+// an array or object whose elements are being parsed.
+enum Frame<'a, T> {
+    Array(Vec<T>),
+    // members so far and the key of the member being parsed.
+    Object(Vec<(Cow<'a, str>, T)>, Cow<'a, str>),
+}
+
+pub struct Parser<R> {
     reader: R,
+    options: ParserOptions,
+    // the index of the input from `base` on, when the options allow one.
+    index: Option<StructuralIndex>,
+    base: usize,
 }
 
-impl<R: Reader> Parser<R> {
+impl<'a, R: Reader<'a>> Parser<R> {
     pub fn new(r: R) -> Self {
-        Parser { reader: r }
+        Self::with_options(r, ParserOptions::default())
+    }
+
+    pub fn with_options(r: R, options: ParserOptions) -> Self {
+        Parser {
+            reader: r,
+            options,
+            index: None,
+            base: 0,
+        }
     }
 
     pub fn parse(&mut self) -> Result<Value, Err> {
+        self.parse_tree()
+    }
+
+    pub fn parse_borrowed(&mut self) -> Result<ValueRef<'a>, Err> {
+        self.parse_tree()
+    }
+
+    fn parse_tree<T: Tree<'a>>(&mut self) -> Result<T, Err> {
+        let start = self.reader.pos();
+        self.parse_root().map_err(|e| {
+            // the error is at the last byte read.
+            let bs = self.reader.range(start, self.reader.pos());
+            let bs = &bs[..bs.len().saturating_sub(1)];
+            let line_start = bs.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            let line = bs.iter().filter(|&&b| b == b'\n').count() + 1;
+            // UTF-8 continuation bytes don't start a character.
+            let column = bs[line_start..]
+                .iter()
+                .filter(|&&b| b & 0xC0 != 0x80)
+                .count()
+                + 1;
+            e.with_position(line, column)
+        })
+    }
+
+    fn parse_root<T: Tree<'a>>(&mut self) -> Result<T, Err> {
+        if self.reader.rest().len() > self.options.limits.max_input_size {
+            return Err(Err::from_kind(ErrKind::InputTooLarge));
+        }
+        // the index would take quotes in comments, or single-quoted strings, for tokens.
+        if !self.options.comments && !self.options.single_quotes {
+            self.base = self.reader.pos();
+            self.index = Some(scan::structural_index(self.reader.rest()));
+        }
         let val = self.parse_value()?;
         if self.skip_spaces().is_none() {
             Ok(val)
@@ -23,66 +190,232 @@ impl<R: Reader> Parser<R> {
         }
     }
 
-    fn parse_value(&mut self) -> Result<Value, Err> {
-        match self.skip_spaces() {
-            Some(b'"') => self.parse_string(),
-            c @ Some(b'0'..=b'9') | c @ Some(b'-') => self.parse_number(c.unwrap()),
-            Some(b'{') => self.parse_object(),
-            Some(b'[') => self.parse_array(),
-            Some(b't') => self.parse_true(),
-            Some(b'f') => self.parse_false(),
-            Some(b'n') => self.parse_null(),
+    // nested values are kept on an explicit stack instead of recursing,
+    // so deep input can't overflow the call stack.
+    fn parse_value<T: Tree<'a>>(&mut self) -> Result<T, Err> {
+        let mut stack: Vec<Frame<'a, T>> = Vec::new();
+        loop {
+            let b = self.skip_spaces().ok_or_else(Err::new)?;
+            if (b == b'[' || b == b'{') && stack.len() >= self.options.limits.max_depth {
+                return Err(Err::from_kind(ErrKind::DepthLimitExceeded));
+            }
+            let mut val = match b {
+                b'[' if self.peek_spaces() == Some(b']') => {
+                    self.forward();
+                    T::array(Vec::new())
+                }
+                b'[' => {
+                    stack.push(Frame::Array(Vec::new()));
+                    continue;
+                }
+                b'{' if self.peek_spaces() == Some(b'}') => {
+                    self.forward();
+                    T::object(Vec::new())
+                }
+                b'{' => {
+                    let key = self.parse_key()?;
+                    stack.push(Frame::Object(Vec::new(), key));
+                    continue;
+                }
+                b => self.parse_scalar(b)?,
+            };
+
+            // adds the value to its parent, and closes parents which end after it.
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(val),
+                    Some(Frame::Array(arr)) => {
+                        arr.push(val);
+                        match self.skip_spaces() {
+                            Some(b',') if self.trailing_comma(b']') => (),
+                            Some(b',') => break,
+                            Some(b']') => (),
+                            _ => return Err(Err::new()),
+                        }
+                    }
+                    Some(Frame::Object(members, key)) => {
+                        if members.len() >= self.options.limits.max_object_members {
+                            return Err(Err::from_kind(ErrKind::TooManyMembers));
+                        }
+                        members.push((mem::take(key), val));
+                        match self.skip_spaces() {
+                            Some(b',') if self.trailing_comma(b'}') => (),
+                            Some(b',') => {
+                                *key = self.parse_key()?;
+                                break;
+                            }
+                            Some(b'}') => (),
+                            _ => return Err(Err::new()),
+                        }
+                    }
+                }
+                val = match stack.pop() {
+                    Some(Frame::Array(arr)) => T::array(arr),
+                    Some(Frame::Object(members, _)) => T::object(members),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    // parses a value other than an array or object, after its first byte.
+    fn parse_scalar<T: Tree<'a>>(&mut self, first: u8) -> Result<T, Err> {
+        match first {
+            b'"' => Ok(T::string(self.parse_string(b'"')?)),
+            b'\'' if self.options.single_quotes => Ok(T::string(self.parse_string(b'\'')?)),
+            b'-' if self.options.special_floats && self.cur() == Some(b'I') => {
+                self.match_next_bytes(b"Infinity")?;
+                Ok(T::number(Number::from(f64::NEG_INFINITY)))
+            }
+            b'+' if self.options.relaxed_numbers
+                && self.options.special_floats
+                && self.cur() == Some(b'I') =>
+            {
+                self.match_next_bytes(b"Infinity")?;
+                Ok(T::number(Number::from(f64::INFINITY)))
+            }
+            b'0'..=b'9' | b'-' => self.parse_number(first),
+            b'+' | b'.' if self.options.relaxed_numbers => self.parse_number(first),
+            b't' => self.parse_true(),
+            b'f' => self.parse_false(),
+            b'n' => self.parse_null(),
+            b'N' if self.options.special_floats => {
+                self.match_next_bytes(b"aN")?;
+                Ok(T::number(Number::from(f64::NAN)))
+            }
+            b'I' if self.options.special_floats => {
+                self.match_next_bytes(b"nfinity")?;
+                Ok(T::number(Number::from(f64::INFINITY)))
+            }
+            _ => Err(Err::new()),
+        }
+    }
+
+    // parses an object key and the following colon.
+    fn parse_key(&mut self) -> Result<Cow<'a, str>, Err> {
+        let key = match self.skip_spaces() {
+            Some(b'"') => self.parse_string(b'"')?,
+            Some(b'\'') if self.options.single_quotes => self.parse_string(b'\'')?,
+            Some(b) if self.options.unquoted_keys && is_identifier_start(b) => {
+                self.parse_identifier()?
+            }
             _ => return Err(Err::new()),
+        };
+        match self.skip_spaces() {
+            Some(b':') => Ok(key),
+            _ => Err(Err::new()),
         }
     }
 
-    fn parse_string(&mut self) -> Result<Value, Err> {
-        let mut s = String::new();
-        let mut buf = Vec::new();
-        let mut closed = false;
-        let mut escaped = false;
-
-        while let Some(b) = self.next() {
-            if escaped {
-                s.push(match b {
-                    b'"' | b'\\' | b'/' => b as char,
-                    b'b' => '\x08',
-                    b'f' => '\x0C',
-                    b'n' => '\n',
-                    b'r' => '\r',
-                    b't' => '\t',
-                    b'u' => self.hex_to_char()?,
-                    _ => return Err(Err::new()),
-                });
-                escaped = false;
-            } else {
-                match b {
-                    b'\\' => {
-                        s.push_str(bytes_to_string(buf.as_slice()).as_str());
-                        buf.clear();
-                        escaped = true;
-                    }
-                    b'"' => {
-                        s.push_str(bytes_to_string(buf.as_slice()).as_str());
-                        buf.clear();
-                        closed = true;
-                        break;
-                    }
-                    _ => {
-                        buf.push(b);
+    fn parse_string(&mut self, quote: u8) -> Result<Cow<'a, str>, Err> {
+        if let Some(end) = self.plain_string_end() {
+            let start = self.reader.pos();
+            self.mov(end + 1 - start);
+            let raw = bytes_to_str(self.reader.range(start, end))?;
+            if raw.len() > self.options.limits.max_string_length {
+                return Err(Err::from_kind(ErrKind::StringTooLong));
+            }
+            return Ok(Cow::Borrowed(raw));
+        }
+
+        // unescaped bytes since the last escape sequence.
+        let mut start = self.reader.pos();
+        let mut s: Option<String> = None;
+
+        loop {
+            let n = scan::string_end(self.reader.rest(), quote);
+            self.mov(n);
+            match self.next() {
+                Some(b'\\') => {
+                    let raw = bytes_to_str(self.reader.range(start, self.reader.pos() - 1))?;
+                    s.get_or_insert_with(String::new).push_str(raw);
+                    let c = match self.next() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'\'') if self.options.single_quotes => '\'',
+                        Some(b'b') => '\x08',
+                        Some(b'f') => '\x0C',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.hex_to_char()?,
+                        Some(b @ b'\r') | Some(b @ b'\n') if self.options.line_continuations => {
+                            if b == b'\r' && self.cur() == Some(b'\n') {
+                                self.forward();
+                            }
+                            start = self.reader.pos();
+                            continue;
+                        }
+                        // U+2028 and U+2029
+                        Some(0xE2)
+                            if self.options.line_continuations
+                                && matches!(
+                                    self.slice(2),
+                                    Some([0x80, 0xA8]) | Some([0x80, 0xA9])
+                                ) =>
+                        {
+                            self.mov(2);
+                            start = self.reader.pos();
+                            continue;
+                        }
+                        _ => return Err(Err::new()),
+                    };
+                    s.get_or_insert_with(String::new).push(c);
+                    start = self.reader.pos();
+                }
+                Some(b) if b == quote => {
+                    let raw = bytes_to_str(self.reader.range(start, self.reader.pos() - 1))?;
+                    let len = s.as_ref().map_or(0, String::len) + raw.len();
+                    if len > self.options.limits.max_string_length {
+                        return Err(Err::from_kind(ErrKind::StringTooLong));
                     }
+                    return Ok(match s {
+                        Some(mut s) => {
+                            s.push_str(raw);
+                            Cow::Owned(s)
+                        }
+                        None => Cow::Borrowed(raw),
+                    });
                 }
+                // control characters must be escaped.
+                _ => return Err(Err::new()),
             }
         }
+    }
 
-        if closed {
-            Ok(Value::String(s))
-        } else {
-            Err(Err::new())
+    // the closing quote of a string without escapes or control characters, taken from the
+    // index. the next token after an opening quote is the closing one.
+    fn plain_string_end(&self) -> Option<usize> {
+        let index = self.index.as_ref()?;
+        let start = self.reader.pos() - self.base;
+        let end = index.tokens.next(start)?;
+        if index.specials.any(start, end) {
+            return None;
         }
+        Some(self.base + end)
     }
 
     fn hex_to_char(&mut self) -> Result<char, Err> {
+        let n = match self.parse_hex()? {
+            // high surrogate must be followed by an escaped low surrogate.
+            hi @ 0xD800..=0xDBFF => {
+                self.match_next_bytes(b"\\u")?;
+                match self.parse_hex()? {
+                    lo @ 0xDC00..=0xDFFF => 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00),
+                    _ => return Err(Err::new()),
+                }
+            }
+            0xDC00..=0xDFFF => return Err(Err::new()),
+            n => n,
+        };
+        match char::from_u32(n) {
+            Some(c) => Ok(c),
+            None => Err(Err::new()),
+        }
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, Err> {
         match self.slice(4) {
             Some(bs) => {
                 let mut n: u32 = 0;
@@ -96,141 +429,232 @@ impl<R: Reader> Parser<R> {
                     } as u32;
                 }
                 self.mov(4);
-                match char::from_u32(n) {
-                    Some(c) => Ok(c),
-                    None => Err(Err::new()),
-                }
+                Ok(n)
             }
             None => Err(Err::new()),
         }
     }
 
-    fn parse_number(&mut self, first: u8) -> Result<Value, Err> {
+    fn parse_number<T: Tree<'a>>(&mut self, first: u8) -> Result<T, Err> {
         enum State {
             Minus,
             Zero,
-            Dot,
             Integer,
+            Dot,
             Fraction,
-            ExpSign,
             Exp,
+            ExpSign,
+            ExpDigits,
         }
 
+        let relaxed = self.options.relaxed_numbers;
         let mut state = match first {
-            b'-' => State::Minus,
+            b'-' | b'+' => State::Minus,
             b'0' => State::Zero,
             b'1'..=b'9' => State::Integer,
+            b'.' => State::Dot,
             _ => panic!("invalid first byte."),
         };
+        // digits before the dot, without which `5.` would be just `.`.
+        let mut int_digits = first.is_ascii_digit();
 
-        let mut s = String::new();
-        s.push(first as char);
+        let start = self.reader.pos() - 1;
         while let Some(b) = self.cur() {
             state = match state {
                 State::Minus => match b {
                     b'0' => State::Zero,
                     b'1'..=b'9' => State::Integer,
+                    b'.' if relaxed => State::Dot,
                     _ => break,
                 },
                 State::Zero => match b {
+                    b'x' | b'X' if self.options.hex_numbers => {
+                        self.forward();
+                        return self.parse_hex_number(first == b'-');
+                    }
                     b'.' => State::Dot,
-                    _ => break,
-                },
-                State::Dot => match b {
-                    b'0'..=b'9' => State::Fraction,
+                    b'e' | b'E' => State::Exp,
                     _ => break,
                 },
                 State::Integer => match b {
                     b'0'..=b'9' => State::Integer,
-                    b'.' => State::Fraction,
-                    b'e' | b'E' => State::ExpSign,
+                    b'.' => State::Dot,
+                    b'e' | b'E' => State::Exp,
                     _ => break,
                 },
-                State::Fraction => match b {
-                    b'0'..=b'9' => State::Integer,
-                    b'e' | b'E' => State::ExpSign,
+                State::Dot => match b {
+                    b'0'..=b'9' => State::Fraction,
+                    b'e' | b'E' if relaxed && int_digits => State::Exp,
                     _ => break,
                 },
-                State::ExpSign => match b {
-                    b'+' | b'-' => State::Exp,
+                State::Fraction => match b {
+                    b'0'..=b'9' => State::Fraction,
+                    b'e' | b'E' => State::Exp,
                     _ => break,
                 },
                 State::Exp => match b {
-                    b'0'..=b'9' => State::Exp,
+                    b'+' | b'-' => State::ExpSign,
+                    b'0'..=b'9' => State::ExpDigits,
+                    _ => break,
+                },
+                State::ExpSign | State::ExpDigits => match b {
+                    b'0'..=b'9' => State::ExpDigits,
                     _ => break,
                 },
             };
-            s.push(b as char);
             self.forward();
+            if let State::Zero | State::Integer = state {
+                int_digits = true;
+            }
+            // digit runs are skipped in bulk.
+            if let State::Integer | State::Fraction | State::ExpDigits = state {
+                let n = scan::digits(self.reader.rest());
+                self.mov(n);
+            }
         }
 
-        Ok(Value::Number(s.parse().unwrap()))
-    }
+        match state {
+            State::Zero | State::Integer | State::Fraction | State::ExpDigits => (),
+            State::Dot if relaxed && int_digits => (),
+            _ => return Err(Err::new()),
+        }
 
-    fn parse_object(&mut self) -> Result<Value, Err> {
-        let mut obj = HashMap::new();
-        loop {
-            let key;
-            match self.skip_spaces() {
-                Some(b'"') => {
-                    key = self.parse_string()?.as_string().unwrap().clone();
-                }
-                _ => return Err(Err::new()),
-            }
+        let s = bytes_to_str(self.reader.range(start, self.reader.pos()))?;
+        let s = if relaxed {
+            to_strict_number(s)
+        } else {
+            Cow::Borrowed(s)
+        };
+        match Number::from_json_str(&s) {
+            Some(n) => Ok(T::number(n)),
+            None => Err(Err::new()),
+        }
+    }
 
-            match self.skip_spaces() {
-                Some(b':') => (),
-                _ => return Err(Err::new()),
-            }
+    fn parse_hex_number<T: Tree<'a>>(&mut self, negative: bool) -> Result<T, Err> {
+        let mut n: u64 = 0;
+        let mut digits = 0;
+        while let Some(b) = self.cur() {
+            let d = match b {
+                b'0'..=b'9' => b - b'0',
+                b'A'..=b'F' => b - b'A' + 10,
+                b'a'..=b'f' => b - b'a' + 10,
+                _ => break,
+            };
+            n = n
+                .checked_mul(16)
+                .and_then(|n| n.checked_add(d as u64))
+                .ok_or_else(Err::new)?;
+            digits += 1;
+            self.forward();
+        }
+        if digits == 0 {
+            return Err(Err::new());
+        }
 
-            obj.insert(key, self.parse_value()?);
+        if !negative {
+            Ok(T::number(Number::from(n)))
+        } else if n <= i64::MAX as u64 + 1 {
+            Ok(T::number(Number::from((n as i64).wrapping_neg())))
+        } else {
+            Err(Err::new())
+        }
+    }
 
-            match self.skip_spaces() {
-                Some(b',') => (),
-                Some(b'}') => break,
-                _ => return Err(Err::new()),
+    fn parse_identifier(&mut self) -> Result<Cow<'a, str>, Err> {
+        let start = self.reader.pos() - 1;
+        while let Some(b) = self.cur() {
+            if !is_identifier_start(b) && !b.is_ascii_digit() {
+                break;
             }
+            self.forward();
         }
-        Ok(Value::Object(Box::new(obj)))
+        Ok(Cow::Borrowed(bytes_to_str(
+            self.reader.range(start, self.reader.pos()),
+        )?))
     }
 
-    fn parse_array(&mut self) -> Result<Value, Err> {
-        let mut arr = Vec::new();
-        loop {
-            let v = self.parse_value()?;
-            arr.push(v);
-            match self.skip_spaces() {
-                Some(b',') => (),
-                Some(b']') => break,
-                _ => return Err(Err::new()),
-            }
+    // consumes the closing bracket if it follows a trailing comma.
+    fn trailing_comma(&mut self, close: u8) -> bool {
+        if self.options.trailing_commas && self.peek_spaces() == Some(close) {
+            self.forward();
+            true
+        } else {
+            false
         }
-        Ok(Value::Array(arr))
     }
 
-    fn parse_true(&mut self) -> Result<Value, Err> {
+    fn parse_true<T: Tree<'a>>(&mut self) -> Result<T, Err> {
         self.match_next_bytes(b"rue")?;
-        Ok(Value::Boolean(true))
+        Ok(T::boolean(true))
     }
 
-    fn parse_false(&mut self) -> Result<Value, Err> {
+    fn parse_false<T: Tree<'a>>(&mut self) -> Result<T, Err> {
         self.match_next_bytes(b"alse")?;
-        Ok(Value::Boolean(false))
+        Ok(T::boolean(false))
     }
 
-    fn parse_null(&mut self) -> Result<Value, Err> {
+    fn parse_null<T: Tree<'a>>(&mut self) -> Result<T, Err> {
         self.match_next_bytes(b"ull")?;
-        Ok(Value::Null)
+        Ok(T::null())
     }
 
     fn skip_spaces(&mut self) -> Option<u8> {
-        while let Some(b) = self.next() {
-            match b {
-                b' ' | b'\n' | b'\r' | b'\t' => (),
-                _ => return Some(b),
+        let b = self.peek_spaces();
+        if b.is_some() {
+            self.forward();
+        }
+        b
+    }
+
+    // skips spaces and returns the next byte without consuming it.
+    fn peek_spaces(&mut self) -> Option<u8> {
+        if let Some(index) = &self.index {
+            // whitespace runs up to the next token, or to the end of the input.
+            if is_whitespace(self.cur()?) {
+                let pos = self.reader.pos() - self.base;
+                let n = match index.tokens.next(pos) {
+                    Some(next) => next - pos,
+                    None => self.reader.rest().len(),
+                };
+                self.mov(n);
+            }
+            return self.cur();
+        }
+        loop {
+            let n = scan::whitespace(self.reader.rest());
+            self.mov(n);
+            match self.cur()? {
+                b'/' if self.options.comments && self.skip_comment() => (),
+                b => return Some(b),
+            }
+        }
+    }
+
+    // an unterminated block comment is left unconsumed so that it fails as an unexpected `/`.
+    fn skip_comment(&mut self) -> bool {
+        match self.slice(2) {
+            Some(b"//") => {
+                while let Some(b) = self.next() {
+                    if b == b'\n' {
+                        break;
+                    }
+                }
+                true
+            }
+            Some(b"/*") => {
+                let mut n = 2;
+                while let Some(bs) = self.reader.slice(n + 2) {
+                    if bs.ends_with(b"*/") {
+                        self.mov(n + 2);
+                        return true;
+                    }
+                    n += 1;
+                }
+                false
             }
+            _ => false,
         }
-        None
     }
 
     fn match_next_bytes(&mut self, bs: &[u8]) -> Result<(), Err> {
@@ -261,7 +685,7 @@ impl<R: Reader> Parser<R> {
         self.reader.cur()
     }
 
-    fn slice(&self, n: usize) -> Option<&[u8]> {
+    fn slice(&self, n: usize) -> Option<&'a [u8]> {
         self.reader.slice(n)
     }
 
@@ -274,6 +698,37 @@ impl<R: Reader> Parser<R> {
     }
 }
 
-fn bytes_to_string(bs: &[u8]) -> String {
-    String::from_utf8(bs.to_vec()).unwrap()
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\n' | b'\r' | b'\t')
+}
+
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+// rewrites `+1`, `.5` and `5.` into RFC 8259 numbers.
+fn to_strict_number(s: &str) -> Cow<'_, str> {
+    let unsigned = s.strip_prefix('+').unwrap_or(s);
+    let bs = unsigned.as_bytes();
+    let dot = bs.iter().position(|&b| b == b'.');
+    let leading_dot = dot.is_some_and(|i| i == 0 || (i == 1 && bs[0] == b'-'));
+    let trailing_dot = dot.is_some_and(|i| !bs.get(i + 1).is_some_and(u8::is_ascii_digit));
+    if unsigned.len() == s.len() && !leading_dot && !trailing_dot {
+        return Cow::Borrowed(s);
+    }
+    let mut strict = String::with_capacity(unsigned.len() + 2);
+    for c in unsigned.chars() {
+        if c == '.' && leading_dot {
+            strict.push('0');
+        }
+        strict.push(c);
+        if c == '.' && trailing_dot {
+            strict.push('0');
+        }
+    }
+    Cow::Owned(strict)
+}
+
+fn bytes_to_str(bs: &[u8]) -> Result<&str, Err> {
+    str::from_utf8(bs).map_err(|_| Err::new())
 }
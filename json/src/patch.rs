@@ -0,0 +1,148 @@
+use super::error::Err;
+use super::pointer::escape_token;
+use super::value::Value;
+
+// applies a JSON Patch (RFC 6902). the document is left untouched when any operation fails.
+pub fn apply(doc: &mut Value, patch: &Value) -> Result<(), Err> {
+    let ops = patch.as_array().ok_or_else(Err::new)?;
+    let mut patched = doc.clone();
+    for op in ops {
+        apply_op(&mut patched, op)?;
+    }
+    *doc = patched;
+    Ok(())
+}
+
+fn apply_op(doc: &mut Value, op: &Value) -> Result<(), Err> {
+    let path = get_str(op, "path")?;
+    match get_str(op, "op")? {
+        "add" => {
+            doc.insert(path, get_value(op)?.clone())?;
+        }
+        "remove" => {
+            doc.remove(path).ok_or_else(Err::new)?;
+        }
+        "replace" => {
+            *doc.pointer_mut(path).ok_or_else(Err::new)? = get_value(op)?.clone();
+        }
+        "move" => {
+            let from = get_str(op, "from")?;
+            if from != path {
+                // a value can't be moved into one of its children.
+                if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                    return Err(Err::new());
+                }
+                let val = doc.remove(from).ok_or_else(Err::new)?;
+                doc.insert(path, val)?;
+            }
+        }
+        "copy" => {
+            let from = get_str(op, "from")?;
+            let val = doc.pointer(from).ok_or_else(Err::new)?.clone();
+            doc.insert(path, val)?;
+        }
+        "test" => {
+            if doc.pointer(path) != Some(get_value(op)?) {
+                return Err(Err::new());
+            }
+        }
+        _ => return Err(Err::new()),
+    }
+    Ok(())
+}
+
+fn get_str<'a>(op: &'a Value, key: &str) -> Result<&'a str, Err> {
+    match op.get(key) {
+        Some(Value::String(s)) => Ok(s),
+        _ => Err(Err::new()),
+    }
+}
+
+fn get_value(op: &Value) -> Result<&Value, Err> {
+    op.get("value").ok_or_else(Err::new)
+}
+
+// applies a JSON Merge Patch (RFC 7386).
+pub fn merge(doc: &mut Value, patch: &Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !doc.is_object() {
+                *doc = Value::Object(Box::default());
+            }
+            if let Value::Object(obj) = doc {
+                for (key, val) in patch.iter() {
+                    if val.is_null() {
+                        obj.remove(key);
+                    } else {
+                        merge(obj.entry(key.clone()).or_insert(Value::Null), val);
+                    }
+                }
+            }
+        }
+        _ => *doc = patch.clone(),
+    }
+}
+
+// makes a JSON Patch which turns `a` into `b`.
+pub fn diff(a: &Value, b: &Value) -> Value {
+    let mut ops = Vec::new();
+    diff_into(a, b, "", &mut ops);
+    Value::Array(ops)
+}
+
+fn diff_into(a: &Value, b: &Value, path: &str, ops: &mut Vec<Value>) {
+    if a == b {
+        return;
+    }
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, val) in a.iter() {
+                let path = format!("{}/{}", path, escape_token(key));
+                match b.get(key) {
+                    Some(other) => diff_into(val, other, &path, ops),
+                    None => ops.push(op("remove", &path, None)),
+                }
+            }
+            for (key, val) in b.iter() {
+                if !a.contains_key(key) {
+                    let path = format!("{}/{}", path, escape_token(key));
+                    ops.push(op("add", &path, Some(val)));
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            // only the middle part between the common prefix and suffix is changed.
+            let prefix = a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+            let suffix = a[prefix..]
+                .iter()
+                .rev()
+                .zip(b[prefix..].iter().rev())
+                .take_while(|(x, y)| x == y)
+                .count();
+            let a = &a[prefix..a.len() - suffix];
+            let b = &b[prefix..b.len() - suffix];
+
+            for (i, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+                diff_into(x, y, &format!("{}/{}", path, prefix + i), ops);
+            }
+            let common = a.len().min(b.len());
+            for _ in common..a.len() {
+                ops.push(op("remove", &format!("{}/{}", path, prefix + common), None));
+            }
+            for (i, val) in b.iter().enumerate().skip(common) {
+                ops.push(op("add", &format!("{}/{}", path, prefix + i), Some(val)));
+            }
+        }
+        _ => ops.push(op("replace", path, Some(b))),
+    }
+}
+
+fn op(name: &str, path: &str, val: Option<&Value>) -> Value {
+    let mut op = Value::Object(Box::default());
+    op["op"] = Value::from(name);
+    op["path"] = Value::from(path);
+    if let Some(val) = val {
+        op["value"] = val.clone();
+    }
+    op
+}
@@ -0,0 +1,178 @@
+use std::mem;
+use std::ops::{Index, IndexMut};
+
+use super::error::Err;
+use super::value::Value;
+
+static NULL: Value = Value::Null;
+
+// JSON Pointer (RFC 6901)
+impl Value {
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        let mut target = self;
+        for token in parse_pointer(pointer)? {
+            target = match target {
+                Value::Object(obj) => obj.get(&token)?,
+                Value::Array(arr) => arr.get(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let mut target = self;
+        for token in parse_pointer(pointer)? {
+            target = match target {
+                Value::Object(obj) => obj.get_mut(&token)?,
+                Value::Array(arr) => arr.get_mut(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    // inserts the value at the pointer and returns the replaced one.
+    // `-` as the last token appends the value to an array.
+    pub fn insert(&mut self, pointer: &str, val: Value) -> Result<Option<Value>, Err> {
+        let (parent, last) = match split_pointer(pointer) {
+            Some(v) => v,
+            None if pointer.is_empty() => return Ok(Some(mem::replace(self, val))),
+            None => return Err(Err::new()),
+        };
+
+        match self.pointer_mut(parent) {
+            Some(Value::Object(obj)) => Ok(obj.insert(last, val)),
+            Some(Value::Array(arr)) => {
+                let i = if last == "-" {
+                    arr.len()
+                } else {
+                    parse_index(&last).ok_or_else(Err::new)?
+                };
+                if i > arr.len() {
+                    return Err(Err::new());
+                }
+                arr.insert(i, val);
+                Ok(None)
+            }
+            _ => Err(Err::new()),
+        }
+    }
+
+    // removes the value at the pointer. the root can't be removed.
+    pub fn remove(&mut self, pointer: &str) -> Option<Value> {
+        let (parent, last) = split_pointer(pointer)?;
+        match self.pointer_mut(parent)? {
+            Value::Object(obj) => obj.remove(&last),
+            Value::Array(arr) => {
+                let i = parse_index(&last)?;
+                if i < arr.len() {
+                    Some(arr.remove(i))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    pointer[1..].split('/').map(unescape_token).collect()
+}
+
+// splits the pointer into the parent pointer and the unescaped last token.
+fn split_pointer(pointer: &str) -> Option<(&str, String)> {
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    let i = pointer.rfind('/')?;
+    Some((&pointer[..i], unescape_token(&pointer[i + 1..])?))
+}
+
+fn unescape_token(token: &str) -> Option<String> {
+    let mut s = String::new();
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            s.push(match chars.next() {
+                Some('0') => '~',
+                Some('1') => '/',
+                _ => return None,
+            });
+        } else {
+            s.push(c);
+        }
+    }
+    Some(s)
+}
+
+pub(crate) fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        match self {
+            Value::Object(obj) => obj.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, i: usize) -> &Value {
+        match self {
+            Value::Array(arr) => arr.get(i).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+// a missing key is inserted as null, and null is turned into an empty object.
+impl IndexMut<&str> for Value {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        if self.is_null() {
+            *self = Value::Object(Box::default());
+        }
+        match self {
+            Value::Object(obj) => obj.entry(key.to_string()).or_insert(Value::Null),
+            _ => panic!("cannot index a non-object value with \"{}\"", key),
+        }
+    }
+}
+
+impl IndexMut<usize> for Value {
+    fn index_mut(&mut self, i: usize) -> &mut Value {
+        match self {
+            Value::Array(arr) => {
+                let len = arr.len();
+                match arr.get_mut(i) {
+                    Some(v) => v,
+                    None => panic!("index {} out of range for array of length {}", i, len),
+                }
+            }
+            _ => panic!("cannot index a non-array value with {}", i),
+        }
+    }
+}
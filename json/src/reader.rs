@@ -1,7 +1,13 @@
-pub trait Reader {
+pub trait Reader<'a> {
     fn cur(&self) -> Option<u8>;
-    fn slice(&self, n: usize) -> Option<&[u8]>;
+    fn slice(&self, n: usize) -> Option<&'a [u8]>;
     fn mov(&mut self, n: usize);
+    // offset of the current byte from the start of the input.
+    fn pos(&self) -> usize;
+    // input bytes between two offsets returned by pos().
+    fn range(&self, start: usize, end: usize) -> &'a [u8];
+    // unread bytes, for scanning ahead in bulk.
+    fn rest(&self) -> &'a [u8];
 }
 
 pub struct BytesReader<'a> {
@@ -11,6 +17,7 @@ pub struct BytesReader<'a> {
 }
 
 impl<'a> BytesReader<'a> {
+    #[allow(clippy::redundant_field_names)]
     pub fn new(bytes: &'a [u8]) -> Self {
         BytesReader {
             index: 0,
@@ -20,7 +27,7 @@ impl<'a> BytesReader<'a> {
     }
 }
 
-impl<'a> Reader for BytesReader<'a> {
+impl<'a> Reader<'a> for BytesReader<'a> {
     fn cur(&self) -> Option<u8> {
         if self.index < self.len {
             Some(self.bytes[self.index])
@@ -28,7 +35,7 @@ impl<'a> Reader for BytesReader<'a> {
             None
         }
     }
-    fn slice(&self, n: usize) -> Option<&[u8]> {
+    fn slice(&self, n: usize) -> Option<&'a [u8]> {
         let pos = self.index + n;
         if pos <= self.len {
             Some(&self.bytes[self.index..pos])
@@ -36,7 +43,17 @@ impl<'a> Reader for BytesReader<'a> {
             None
         }
     }
+    #[allow(clippy::assign_op_pattern)]
     fn mov(&mut self, n: usize) {
         self.index = self.index + n;
     }
+    fn pos(&self) -> usize {
+        self.index
+    }
+    fn range(&self, start: usize, end: usize) -> &'a [u8] {
+        &self.bytes[start..end]
+    }
+    fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.index..]
+    }
 }
@@ -0,0 +1,463 @@
+// bulk scanning of the input. `structural_index` finds where every token starts before
+// parsing, simdjson-style, and the parser jumps between them instead of reading whitespace
+// and plain strings byte by byte. the other functions scan runs which the index doesn't
+// cover, like escaped strings and digits. on x86_64 16 bytes are tested at once with SSE2,
+// the rest is scanned a byte at a time; `scalar` holds the byte-at-a-time versions.
+
+use std::convert::TryInto;
+
+// index of the first quote, backslash or control character, or the length of `bs`.
+pub fn string_end(bs: &[u8], quote: u8) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    let i = sse2::string_end(bs, quote);
+    #[cfg(not(target_arch = "x86_64"))]
+    let i = 0;
+    i + scalar::string_end(&bs[i..], quote)
+}
+
+// number of leading whitespace bytes.
+pub fn whitespace(bs: &[u8]) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    let i = sse2::whitespace(bs);
+    #[cfg(not(target_arch = "x86_64"))]
+    let i = 0;
+    i + scalar::whitespace(&bs[i..])
+}
+
+// number of leading ascii digits.
+pub fn digits(bs: &[u8]) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    let i = sse2::digits(bs);
+    #[cfg(not(target_arch = "x86_64"))]
+    let i = 0;
+    i + scalar::digits(&bs[i..])
+}
+
+// bits set at the first byte of each token: `{ } [ ] : ,` and quotes outside strings, and
+// the first byte of each other run of non-whitespace outside strings, like a number.
+// `specials` marks backslashes and control characters inside strings. only double quotes
+// start strings, so the parser doesn't use it with comments or single quotes.
+pub fn structural_index(bs: &[u8]) -> StructuralIndex {
+    #[cfg(target_arch = "x86_64")]
+    let index = index_with(bs, sse2::classify);
+    #[cfg(not(target_arch = "x86_64"))]
+    let index = scalar::structural_index(bs);
+    index
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StructuralIndex {
+    pub tokens: Bitmap,
+    pub specials: Bitmap,
+}
+
+// a bit for each byte of the input, 64 bytes to a word.
+#[derive(Debug, PartialEq)]
+pub struct Bitmap(Vec<u64>);
+
+impl Bitmap {
+    // the first set bit at or after `i`.
+    pub fn next(&self, i: usize) -> Option<usize> {
+        let mut w = i / 64;
+        let mut bits = self.0.get(w)? & (!0 << (i % 64));
+        while bits == 0 {
+            w += 1;
+            bits = *self.0.get(w)?;
+        }
+        Some(w * 64 + bits.trailing_zeros() as usize)
+    }
+
+    // whether any bit in `start..end` is set.
+    pub fn any(&self, start: usize, end: usize) -> bool {
+        if start >= end {
+            return false;
+        }
+        let (first, last) = (start / 64, (end - 1) / 64);
+        (first..=last).any(|w| {
+            let mut bits = self.0.get(w).copied().unwrap_or(0);
+            if w == first {
+                bits &= !0 << (start % 64);
+            }
+            if w == last {
+                bits &= !0 >> (63 - (end - 1) % 64);
+            }
+            bits != 0
+        })
+    }
+}
+
+const BLOCK: usize = 64;
+
+// the bytes of a block in each class, a bit for each.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Classes {
+    quote: u64,
+    backslash: u64,
+    whitespace: u64,
+    // `{ } [ ] : ,`
+    op: u64,
+    control: u64,
+}
+
+// `classify` is told when the block starts inside a string. if it has no quotes it is all
+// string then, and whitespace and operators needn't be looked for.
+fn index_with<F: Fn(&[u8; BLOCK], bool) -> Classes>(bs: &[u8], classify: F) -> StructuralIndex {
+    let words = bs.len().div_ceil(BLOCK);
+    let mut tokens = Vec::with_capacity(words);
+    let mut specials = Vec::with_capacity(words);
+    let mut state = BlockState {
+        escape: false,
+        in_string: 0,
+        after_boundary: true,
+    };
+    let mut blocks = bs.chunks_exact(BLOCK);
+    for block in &mut blocks {
+        let (t, s) = state.next(classify(block.try_into().unwrap(), state.in_string != 0));
+        tokens.push(t);
+        specials.push(s);
+    }
+    let rest = blocks.remainder();
+    if !rest.is_empty() {
+        // padded with whitespace, which starts no token.
+        let mut block = [b' '; BLOCK];
+        block[..rest.len()].copy_from_slice(rest);
+        let (t, s) = state.next(classify(&block, state.in_string != 0));
+        tokens.push(t);
+        specials.push(s);
+    }
+    StructuralIndex {
+        tokens: Bitmap(tokens),
+        specials: Bitmap(specials),
+    }
+}
+
+// what carries over from one block to the next.
+struct BlockState {
+    // the last byte is a backslash which escapes the first byte of the next block.
+    escape: bool,
+    // all ones when the last byte is inside a string.
+    in_string: u64,
+    // the last byte is whitespace, an operator or a quote, or there is none.
+    after_boundary: bool,
+}
+
+impl BlockState {
+    // the tokens and specials of the next block.
+    fn next(&mut self, c: Classes) -> (u64, u64) {
+        let quote = c.quote & !self.escaped(c.backslash);
+        // from an opening quote up to the byte before the closing one.
+        let in_string = prefix_xor(quote) ^ self.in_string;
+        self.in_string = ((in_string as i64) >> 63) as u64;
+        let outside = !in_string & !quote;
+
+        let boundary = c.whitespace | c.op | c.quote;
+        let after_boundary = boundary << 1 | self.after_boundary as u64;
+        self.after_boundary = boundary >> 63 == 1;
+        let scalars = outside & !(c.whitespace | c.op) & after_boundary;
+
+        let tokens = (c.op & outside) | quote | scalars;
+        let specials = (c.backslash | c.control) & in_string;
+        (tokens, specials)
+    }
+
+    // the bytes following an odd run of backslashes. backslashes are rare, so they are
+    // taken one at a time.
+    fn escaped(&mut self, backslash: u64) -> u64 {
+        let mut escaped = self.escape as u64;
+        self.escape = false;
+        let mut rest = backslash & !escaped;
+        while rest != 0 {
+            let bit = rest & rest.wrapping_neg();
+            if bit == 1 << 63 {
+                self.escape = true;
+            }
+            // the escaped byte doesn't escape the one after it, even if it's a backslash.
+            escaped |= bit << 1;
+            rest &= !(bit | bit << 1);
+        }
+        escaped
+    }
+}
+
+// each bit xor-ed with all the bits below it, which is set between pairs of quotes.
+fn prefix_xor(mut bits: u64) -> u64 {
+    for shift in &[1, 2, 4, 8, 16, 32] {
+        bits ^= bits << shift;
+    }
+    bits
+}
+
+pub mod scalar {
+    use super::{index_with, Classes, StructuralIndex, BLOCK};
+
+    pub fn string_end(bs: &[u8], quote: u8) -> usize {
+        bs.iter()
+            .position(|&b| b == quote || b == b'\\' || b < 0x20)
+            .unwrap_or(bs.len())
+    }
+
+    pub fn whitespace(bs: &[u8]) -> usize {
+        bs.iter()
+            .position(|&b| !matches!(b, b' ' | b'\n' | b'\r' | b'\t'))
+            .unwrap_or(bs.len())
+    }
+
+    pub fn digits(bs: &[u8]) -> usize {
+        bs.iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(bs.len())
+    }
+
+    pub fn structural_index(bs: &[u8]) -> StructuralIndex {
+        index_with(bs, classify)
+    }
+
+    // every byte is looked at, even in a block which is all string.
+    fn classify(block: &[u8; BLOCK], _: bool) -> Classes {
+        let mut c = Classes::default();
+        for (i, &b) in block.iter().enumerate() {
+            let bit = 1 << i;
+            match b {
+                b'"' => c.quote |= bit,
+                b'\\' => c.backslash |= bit,
+                b' ' | b'\n' | b'\r' | b'\t' => c.whitespace |= bit,
+                b'{' | b'}' | b'[' | b']' | b':' | b',' => c.op |= bit,
+                _ => (),
+            }
+            if b < 0x20 {
+                c.control |= bit;
+            }
+        }
+        c
+    }
+}
+
+// each function scans whole 16-byte blocks only. it returns the index of the first match,
+// or the length of the blocks scanned so that the caller finishes the tail.
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    use std::arch::x86_64::*;
+
+    use super::Classes;
+
+    const BLOCK: usize = 16;
+
+    // classifies the 64 bytes of an index block 16 at a time.
+    pub(super) fn classify(block: &[u8; super::BLOCK], in_string: bool) -> Classes {
+        let mut c = Classes::default();
+        // SAFETY: the 16-byte loads are in bounds, and SSE2 is always available on x86_64.
+        unsafe {
+            let load = |i: usize| _mm_loadu_si128(block.as_ptr().add(i * BLOCK) as *const __m128i);
+            let bits = |m: __m128i, i: usize| (_mm_movemask_epi8(m) as u64) << (i * BLOCK);
+            let control = _mm_set1_epi8(0x1F);
+            for i in 0..super::BLOCK / BLOCK {
+                let v = load(i);
+                let eq = |b: u8| _mm_cmpeq_epi8(v, _mm_set1_epi8(b as i8));
+                c.quote |= bits(eq(b'"'), i);
+                c.backslash |= bits(eq(b'\\'), i);
+                // b <= 0x1F as unsigned, when max(b, 0x1F) == 0x1F.
+                c.control |= bits(_mm_cmpeq_epi8(_mm_max_epu8(v, control), control), i);
+            }
+            if in_string && c.quote == 0 {
+                return c;
+            }
+            for i in 0..super::BLOCK / BLOCK {
+                let v = load(i);
+                let eq = |b: u8| _mm_cmpeq_epi8(v, _mm_set1_epi8(b as i8));
+                c.whitespace |= bits(
+                    _mm_or_si128(
+                        _mm_or_si128(eq(b' '), eq(b'\n')),
+                        _mm_or_si128(eq(b'\r'), eq(b'\t')),
+                    ),
+                    i,
+                );
+                // `[` and `]` are `{` and `}` without the 0x20 bit.
+                let brackets = _mm_or_si128(v, _mm_set1_epi8(0x20));
+                let brackets = _mm_or_si128(
+                    _mm_cmpeq_epi8(brackets, _mm_set1_epi8(b'{' as i8)),
+                    _mm_cmpeq_epi8(brackets, _mm_set1_epi8(b'}' as i8)),
+                );
+                c.op |= bits(_mm_or_si128(brackets, _mm_or_si128(eq(b':'), eq(b','))), i);
+            }
+        }
+        c
+    }
+
+    // calls `f` with each block and stops at the first non-zero match mask.
+    fn scan(bs: &[u8], f: impl Fn(__m128i) -> i32) -> usize {
+        let mut i = 0;
+        while i + BLOCK <= bs.len() {
+            // SAFETY: the block is in bounds, and SSE2 is always available on x86_64.
+            let mask = f(unsafe { _mm_loadu_si128(bs.as_ptr().add(i) as *const __m128i) });
+            if mask != 0 {
+                return i + mask.trailing_zeros() as usize;
+            }
+            i += BLOCK;
+        }
+        i
+    }
+
+    pub(super) fn string_end(bs: &[u8], quote: u8) -> usize {
+        // SAFETY: SSE2 is always available on x86_64.
+        unsafe {
+            let quote = _mm_set1_epi8(quote as i8);
+            let backslash = _mm_set1_epi8(b'\\' as i8);
+            let control = _mm_set1_epi8(0x1F);
+            scan(bs, |v| {
+                // b <= 0x1F as unsigned, when max(b, 0x1F) == 0x1F.
+                let ctl = _mm_cmpeq_epi8(_mm_max_epu8(v, control), control);
+                let m = _mm_or_si128(_mm_cmpeq_epi8(v, quote), _mm_cmpeq_epi8(v, backslash));
+                _mm_movemask_epi8(_mm_or_si128(m, ctl))
+            })
+        }
+    }
+
+    pub(super) fn whitespace(bs: &[u8]) -> usize {
+        // SAFETY: SSE2 is always available on x86_64.
+        unsafe {
+            let space = _mm_set1_epi8(b' ' as i8);
+            let lf = _mm_set1_epi8(b'\n' as i8);
+            let cr = _mm_set1_epi8(b'\r' as i8);
+            let tab = _mm_set1_epi8(b'\t' as i8);
+            scan(bs, |v| {
+                let m = _mm_or_si128(
+                    _mm_or_si128(_mm_cmpeq_epi8(v, space), _mm_cmpeq_epi8(v, lf)),
+                    _mm_or_si128(_mm_cmpeq_epi8(v, cr), _mm_cmpeq_epi8(v, tab)),
+                );
+                !_mm_movemask_epi8(m) & 0xFFFF
+            })
+        }
+    }
+
+    pub(super) fn digits(bs: &[u8]) -> usize {
+        // SAFETY: SSE2 is always available on x86_64.
+        unsafe {
+            let zero = _mm_set1_epi8(b'0' as i8);
+            let nine = _mm_set1_epi8(9);
+            scan(bs, |v| {
+                // b - '0' <= 9 as unsigned.
+                let d = _mm_sub_epi8(v, zero);
+                let m = _mm_cmpeq_epi8(_mm_max_epu8(d, nine), nine);
+                !_mm_movemask_epi8(m) & 0xFFFF
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every byte value at every offset around the 16-byte blocks.
+    fn inputs() -> Vec<Vec<u8>> {
+        let mut inputs = Vec::new();
+        for len in 0..40 {
+            for b in 0..=255u8 {
+                for i in (0..len).step_by(7) {
+                    let mut bs: Vec<u8> = b"a 1\t".iter().copied().cycle().take(len).collect();
+                    bs[i] = b;
+                    inputs.push(bs);
+                }
+            }
+        }
+        inputs.push(b"  \n\t\r 12345678901234567890 \"x\\y\" \x80\xff".to_vec());
+        inputs
+    }
+
+    #[test]
+    fn test_same_as_scalar() {
+        for bs in inputs() {
+            for &quote in b"\"'" {
+                assert_eq!(
+                    scalar::string_end(&bs, quote),
+                    string_end(&bs, quote),
+                    "{:?}",
+                    bs
+                );
+            }
+            assert_eq!(scalar::whitespace(&bs), whitespace(&bs), "{:?}", bs);
+            assert_eq!(scalar::digits(&bs), digits(&bs), "{:?}", bs);
+        }
+    }
+
+    fn bits(bitmap: &Bitmap) -> Vec<usize> {
+        let mut bits = Vec::new();
+        let mut i = 0;
+        while let Some(b) = bitmap.next(i) {
+            bits.push(b);
+            i = b + 1;
+        }
+        bits
+    }
+
+    // the index worked out a byte at a time, without the bit tricks.
+    fn reference(bs: &[u8]) -> (Vec<usize>, Vec<usize>) {
+        let (mut tokens, mut specials) = (Vec::new(), Vec::new());
+        let (mut escaped, mut inside, mut after_boundary) = (false, false, true);
+        for (i, &b) in bs.iter().enumerate() {
+            let quote = b == b'"' && !escaped;
+            escaped = b == b'\\' && !escaped;
+            if quote {
+                inside = !inside;
+            }
+            let ws = matches!(b, b' ' | b'\n' | b'\r' | b'\t');
+            let op = matches!(b, b'{' | b'}' | b'[' | b']' | b':' | b',');
+            let outside = !inside && !quote;
+            if quote || (outside && (op || (!ws && after_boundary))) {
+                tokens.push(i);
+            }
+            if inside && (b == b'\\' || b < 0x20) {
+                specials.push(i);
+            }
+            after_boundary = ws || op || b == b'"';
+        }
+        (tokens, specials)
+    }
+
+    // json-ish bytes, with runs of backslashes and strings across the 64-byte blocks.
+    fn documents() -> Vec<Vec<u8>> {
+        let alphabet = b"\"\\\\\\ {}[]:,a1\n\x01\"";
+        let mut documents = Vec::new();
+        let mut seed = 1u32;
+        for len in (0..300).step_by(3) {
+            for _ in 0..20 {
+                let bs = (0..len)
+                    .map(|_| {
+                        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                        alphabet[(seed >> 16) as usize % alphabet.len()]
+                    })
+                    .collect();
+                documents.push(bs);
+            }
+        }
+        for n in 0..70 {
+            let pad = " ".repeat(n);
+            documents.push(
+                format!(r#"{}["a\\\"b", {{"k": "{}"}}, 12]"#, pad, "\\".repeat(n)).into_bytes(),
+            );
+            documents.push(format!(r#"{}"{}" x"#, "\\".repeat(n), pad).into_bytes());
+        }
+        documents
+    }
+
+    #[test]
+    fn test_structural_index() {
+        for bs in inputs().into_iter().chain(documents()) {
+            let index = structural_index(&bs);
+            let (tokens, specials) = reference(&bs);
+            assert_eq!(tokens, bits(&index.tokens), "{:?}", bs);
+            assert_eq!(specials, bits(&index.specials), "{:?}", bs);
+
+            let scalar = scalar::structural_index(&bs);
+            assert_eq!(tokens, bits(&scalar.tokens), "{:?}", bs);
+            assert_eq!(specials, bits(&scalar.specials), "{:?}", bs);
+
+            for start in (0..=bs.len()).step_by(5) {
+                for end in (start..=bs.len()).step_by(11) {
+                    let any = specials.iter().any(|&i| start <= i && i < end);
+                    assert_eq!(any, index.specials.any(start, end), "{:?}", bs);
+                }
+            }
+        }
+    }
+}
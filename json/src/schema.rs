@@ -0,0 +1,488 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+use super::error::Err;
+use super::pointer::escape_token;
+use super::value::Value;
+
+// JSON Schema (draft 2020-12) core and validation vocabulary.
+// `$ref` can only refer to a location within the same document.
+#[derive(Debug)]
+pub struct Schema {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    // JSON Pointer to the failing location of the instance.
+    pub instance_path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.instance_path, self.message)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Bool(bool),
+    Keywords(Box<Keywords>),
+}
+
+#[derive(Debug, Default)]
+struct Keywords {
+    reference: Option<usize>,
+    types: Option<Vec<String>>,
+    enumeration: Option<Vec<Value>>,
+    constant: Option<Value>,
+
+    all_of: Vec<usize>,
+    any_of: Vec<usize>,
+    one_of: Vec<usize>,
+    not: Option<usize>,
+
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    multiple_of: Option<f64>,
+
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<Regex>,
+
+    prefix_items: Vec<usize>,
+    items: Option<usize>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    unique_items: bool,
+
+    properties: Vec<(String, usize)>,
+    additional_properties: Option<usize>,
+    required: Vec<String>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+}
+
+impl Schema {
+    pub fn compile(schema: &Value) -> Result<Self, Err> {
+        let mut c = Compiler {
+            root: schema,
+            nodes: Vec::new(),
+            cache: HashMap::new(),
+        };
+        c.compile(String::new())?;
+        check_cycles(&c.nodes)?;
+        Ok(Schema { nodes: c.nodes })
+    }
+
+    pub fn validate(&self, instance: &Value) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.validate_node(0, instance, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_ok()
+    }
+
+    fn validate_node(
+        &self,
+        node: usize,
+        instance: &Value,
+        path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let kw = match &self.nodes[node] {
+            Node::Bool(true) => return,
+            Node::Bool(false) => {
+                return errors.push(error(path, "no value is allowed".to_string()));
+            }
+            Node::Keywords(kw) => kw,
+        };
+        let mut fail = |message: String| errors.push(error(path, message));
+
+        if let Some(types) = &kw.types {
+            if !types.iter().any(|t| is_type(instance, t)) {
+                fail(format!("expected {}", types.join(" or ")));
+            }
+        }
+        if let Some(vals) = &kw.enumeration {
+            if !vals.contains(instance) {
+                fail(format!("{} is not one of the enumerated values", instance));
+            }
+        }
+        if let Some(val) = &kw.constant {
+            if val != instance {
+                fail(format!("expected {}", val));
+            }
+        }
+
+        match instance {
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or(f64::NAN);
+                if let Some(min) = kw.minimum.filter(|min| n < *min) {
+                    fail(format!("{} is less than the minimum {}", n, min));
+                }
+                if let Some(max) = kw.maximum.filter(|max| n > *max) {
+                    fail(format!("{} is greater than the maximum {}", n, max));
+                }
+                if let Some(min) = kw.exclusive_minimum.filter(|min| n <= *min) {
+                    fail(format!("{} is not greater than {}", n, min));
+                }
+                if let Some(max) = kw.exclusive_maximum.filter(|max| n >= *max) {
+                    fail(format!("{} is not less than {}", n, max));
+                }
+                if let Some(m) = kw.multiple_of.filter(|m| !is_multiple(n, *m)) {
+                    fail(format!("{} is not a multiple of {}", n, m));
+                }
+            }
+            Value::String(s) => {
+                let len = s.chars().count();
+                if let Some(min) = kw.min_length.filter(|min| len < *min) {
+                    fail(format!("string is shorter than {} characters", min));
+                }
+                if let Some(max) = kw.max_length.filter(|max| len > *max) {
+                    fail(format!("string is longer than {} characters", max));
+                }
+                if let Some(re) = kw.pattern.as_ref().filter(|re| !re.is_match(s)) {
+                    fail(format!("string doesn't match the pattern {}", re));
+                }
+            }
+            Value::Array(arr) => {
+                if let Some(min) = kw.min_items.filter(|min| arr.len() < *min) {
+                    fail(format!("array has fewer than {} items", min));
+                }
+                if let Some(max) = kw.max_items.filter(|max| arr.len() > *max) {
+                    fail(format!("array has more than {} items", max));
+                }
+                if kw.unique_items && has_duplicates(arr) {
+                    fail("array items are not unique".to_string());
+                }
+            }
+            Value::Object(obj) => {
+                for key in &kw.required {
+                    if !obj.contains_key(key) {
+                        fail(format!("missing required property \"{}\"", key));
+                    }
+                }
+                if let Some(min) = kw.min_properties.filter(|min| obj.len() < *min) {
+                    fail(format!("object has fewer than {} properties", min));
+                }
+                if let Some(max) = kw.max_properties.filter(|max| obj.len() > *max) {
+                    fail(format!("object has more than {} properties", max));
+                }
+            }
+            _ => (),
+        }
+
+        if let Some(r) = kw.reference {
+            self.validate_node(r, instance, path, errors);
+        }
+        for s in &kw.all_of {
+            self.validate_node(*s, instance, path, errors);
+        }
+        if !kw.any_of.is_empty() && self.count_valid(&kw.any_of, instance) == 0 {
+            errors.push(error(path, "no subschema of anyOf matches".to_string()));
+        }
+        if !kw.one_of.is_empty() {
+            let n = self.count_valid(&kw.one_of, instance);
+            if n != 1 {
+                let message = format!("{} subschemas of oneOf match, expected 1", n);
+                errors.push(error(path, message));
+            }
+        }
+        if let Some(s) = kw.not {
+            if self.count_valid(&[s], instance) == 1 {
+                errors.push(error(path, "value matches the schema of not".to_string()));
+            }
+        }
+
+        match instance {
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    let s = match kw.prefix_items.get(i) {
+                        Some(s) => *s,
+                        None => match kw.items {
+                            Some(s) => s,
+                            None => continue,
+                        },
+                    };
+                    self.validate_node(s, item, &format!("{}/{}", path, i), errors);
+                }
+            }
+            Value::Object(obj) => {
+                for (key, val) in obj.iter() {
+                    let item_path = format!("{}/{}", path, escape_token(key));
+                    match kw.properties.iter().find(|(k, _)| k == key) {
+                        Some((_, s)) => self.validate_node(*s, val, &item_path, errors),
+                        None => {
+                            if let Some(s) = kw.additional_properties {
+                                self.validate_node(s, val, &item_path, errors);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn count_valid(&self, nodes: &[usize], instance: &Value) -> usize {
+        nodes
+            .iter()
+            .filter(|s| {
+                let mut errors = Vec::new();
+                self.validate_node(**s, instance, "", &mut errors);
+                errors.is_empty()
+            })
+            .count()
+    }
+}
+
+fn error(path: &str, message: String) -> ValidationError {
+    ValidationError {
+        instance_path: path.to_string(),
+        message,
+    }
+}
+
+fn is_type(v: &Value, t: &str) -> bool {
+    match (t, v) {
+        ("null", Value::Null)
+        | ("boolean", Value::Boolean(_))
+        | ("number", Value::Number(_))
+        | ("string", Value::String(_))
+        | ("array", Value::Array(_))
+        | ("object", Value::Object(_)) => true,
+        ("integer", Value::Number(n)) => {
+            n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+// `n / m` is inexact for decimals like 0.3 / 0.1, so the quotient only has to be within
+// a few ulps of an integer.
+fn is_multiple(n: f64, m: f64) -> bool {
+    let q = n / m;
+    q.is_finite() && (q - q.round()).abs() <= q.abs().max(1.0) * f64::EPSILON * 4.0
+}
+
+// the subschemas applied to the same instance as the node itself.
+fn in_place(node: &Node) -> Vec<usize> {
+    match node {
+        Node::Bool(_) => Vec::new(),
+        Node::Keywords(kw) => {
+            let lists = kw.all_of.iter().chain(&kw.any_of).chain(&kw.one_of);
+            kw.reference
+                .iter()
+                .chain(lists)
+                .chain(&kw.not)
+                .copied()
+                .collect()
+        }
+    }
+}
+
+// rejects a cycle of `$ref`s and combinators which never descends into the instance,
+// such as `{"$ref": "#"}`, as validating it would never end.
+fn check_cycles(nodes: &[Node]) -> Result<(), Err> {
+    // 0: not visited, 1: on the current path, 2: done
+    let mut states = vec![0u8; nodes.len()];
+    for start in 0..nodes.len() {
+        if states[start] != 0 {
+            continue;
+        }
+        states[start] = 1;
+        let mut stack = vec![(start, in_place(&nodes[start]), 0)];
+        while let Some((node, next, i)) = stack.last_mut() {
+            match next.get(*i).copied() {
+                Some(child) => {
+                    *i += 1;
+                    match states[child] {
+                        0 => {
+                            states[child] = 1;
+                            stack.push((child, in_place(&nodes[child]), 0));
+                        }
+                        1 => return Err(Err::new()),
+                        _ => (),
+                    }
+                }
+                None => {
+                    states[*node] = 2;
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn has_duplicates(arr: &[Value]) -> bool {
+    arr.iter()
+        .enumerate()
+        .any(|(i, v)| arr[i + 1..].contains(v))
+}
+
+struct Compiler<'a> {
+    root: &'a Value,
+    nodes: Vec<Node>,
+    // pointer to the subschema -> index of the compiled node
+    cache: HashMap<String, usize>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(&mut self, pointer: String) -> Result<usize, Err> {
+        if let Some(i) = self.cache.get(&pointer) {
+            return Ok(*i);
+        }
+        let root = self.root;
+        let schema = root.pointer(&pointer).ok_or_else(Err::new)?;
+
+        // registered before the children are compiled to allow recursive references.
+        let i = self.nodes.len();
+        self.nodes.push(Node::Bool(true));
+        self.cache.insert(pointer.clone(), i);
+
+        self.nodes[i] = match schema {
+            Value::Boolean(b) => Node::Bool(*b),
+            Value::Object(_) => Node::Keywords(Box::new(self.compile_keywords(&pointer, schema)?)),
+            _ => return Err(Err::new()),
+        };
+        Ok(i)
+    }
+
+    fn compile_keywords(&mut self, pointer: &str, schema: &Value) -> Result<Keywords, Err> {
+        let mut kw = Keywords::default();
+        let sub = |key: &str| format!("{}/{}", pointer, escape_token(key));
+
+        if let Some(r) = schema.get("$ref") {
+            let r = r.as_string().ok_or_else(Err::new)?;
+            if !r.starts_with('#') {
+                return Err(Err::new());
+            }
+            kw.reference = Some(self.compile(percent_decode(&r[1..])?)?);
+        }
+        if let Some(t) = schema.get("type") {
+            kw.types = Some(match t {
+                Value::String(s) => vec![s.clone()],
+                Value::Array(arr) => arr
+                    .iter()
+                    .map(|t| t.as_string().cloned().ok_or_else(Err::new))
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(Err::new()),
+            });
+        }
+        if let Some(vals) = schema.get("enum") {
+            kw.enumeration = Some(vals.as_array().ok_or_else(Err::new)?.clone());
+        }
+        kw.constant = schema.get("const").cloned();
+
+        kw.all_of = self.compile_list(schema, "allOf", &sub("allOf"))?;
+        kw.any_of = self.compile_list(schema, "anyOf", &sub("anyOf"))?;
+        kw.one_of = self.compile_list(schema, "oneOf", &sub("oneOf"))?;
+        if schema.get("not").is_some() {
+            kw.not = Some(self.compile(sub("not"))?);
+        }
+
+        kw.minimum = get_f64(schema, "minimum")?;
+        kw.maximum = get_f64(schema, "maximum")?;
+        kw.exclusive_minimum = get_f64(schema, "exclusiveMinimum")?;
+        kw.exclusive_maximum = get_f64(schema, "exclusiveMaximum")?;
+        kw.multiple_of = get_f64(schema, "multipleOf")?;
+
+        kw.min_length = get_usize(schema, "minLength")?;
+        kw.max_length = get_usize(schema, "maxLength")?;
+        if let Some(p) = schema.get("pattern") {
+            let p = p.as_string().ok_or_else(Err::new)?;
+            kw.pattern = Some(Regex::new(p).map_err(|_| Err::new())?);
+        }
+
+        kw.prefix_items = self.compile_list(schema, "prefixItems", &sub("prefixItems"))?;
+        if schema.get("items").is_some() {
+            kw.items = Some(self.compile(sub("items"))?);
+        }
+        kw.min_items = get_usize(schema, "minItems")?;
+        kw.max_items = get_usize(schema, "maxItems")?;
+        kw.unique_items = schema.get("uniqueItems") == Some(&Value::Boolean(true));
+
+        if let Some(props) = schema.get("properties") {
+            let props = props.as_object().ok_or_else(Err::new)?;
+            for key in props.keys() {
+                let p = format!("{}/{}", sub("properties"), escape_token(key));
+                kw.properties.push((key.clone(), self.compile(p)?));
+            }
+        }
+        if schema.get("additionalProperties").is_some() {
+            kw.additional_properties = Some(self.compile(sub("additionalProperties"))?);
+        }
+        if let Some(required) = schema.get("required") {
+            kw.required = required
+                .as_array()
+                .ok_or_else(Err::new)?
+                .iter()
+                .map(|k| k.as_string().cloned().ok_or_else(Err::new))
+                .collect::<Result<_, _>>()?;
+        }
+        kw.min_properties = get_usize(schema, "minProperties")?;
+        kw.max_properties = get_usize(schema, "maxProperties")?;
+
+        Ok(kw)
+    }
+
+    fn compile_list(
+        &mut self,
+        schema: &Value,
+        key: &str,
+        pointer: &str,
+    ) -> Result<Vec<usize>, Err> {
+        match schema.get(key) {
+            Some(Value::Array(arr)) => (0..arr.len())
+                .map(|i| self.compile(format!("{}/{}", pointer, i)))
+                .collect(),
+            Some(_) => Err(Err::new()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+fn get_f64(schema: &Value, key: &str) -> Result<Option<f64>, Err> {
+    match schema.get(key) {
+        Some(v) => v.as_f64().map(Some).ok_or_else(Err::new),
+        None => Ok(None),
+    }
+}
+
+fn get_usize(schema: &Value, key: &str) -> Result<Option<usize>, Err> {
+    match schema.get(key) {
+        Some(v) => v.as_u64().map(|n| Some(n as usize)).ok_or_else(Err::new),
+        None => Ok(None),
+    }
+}
+
+// decodes the URI fragment of `$ref` into a JSON Pointer.
+fn percent_decode(s: &str) -> Result<String, Err> {
+    let bs = s.as_bytes();
+    let mut buf = Vec::new();
+    let mut i = 0;
+    while i < bs.len() {
+        if bs[i] == b'%' {
+            let hex = s.get(i + 1..i + 3).ok_or_else(Err::new)?;
+            buf.push(u8::from_str_radix(hex, 16).map_err(|_| Err::new())?);
+            i += 3;
+        } else {
+            buf.push(bs[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(buf).map_err(|_| Err::new())
+}
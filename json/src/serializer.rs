@@ -0,0 +1,140 @@
+use std::collections::hash_map;
+use std::fmt;
+use std::fmt::Write;
+use std::slice;
+use std::vec;
+
+use super::value::Value;
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_value(f, self, false)
+    }
+}
+
+impl Value {
+    // indents with two spaces. object keys are sorted so that the output is stable.
+    pub fn to_string_pretty(&self) -> String {
+        let mut s = String::new();
+        write_value(&mut s, self, true).unwrap();
+        s
+    }
+}
+
+// the members of an array or object left to write.
+enum Members<'a> {
+    Array(slice::Iter<'a, Value>),
+    Object(hash_map::Iter<'a, String, Value>),
+    Sorted(vec::IntoIter<(&'a String, &'a Value)>),
+}
+
+impl<'a> Members<'a> {
+    fn next(&mut self) -> Option<(Option<&'a String>, &'a Value)> {
+        match self {
+            Members::Array(iter) => iter.next().map(|v| (None, v)),
+            Members::Object(iter) => iter.next().map(|(k, v)| (Some(k), v)),
+            Members::Sorted(iter) => iter.next().map(|(k, v)| (Some(k), v)),
+        }
+    }
+
+    fn close(&self) -> char {
+        match self {
+            Members::Array(_) => ']',
+            _ => '}',
+        }
+    }
+}
+
+// nested arrays and objects are kept on a stack rather than written recursively, like
+// the parser reads them.
+fn write_value<W: Write>(w: &mut W, v: &Value, pretty: bool) -> fmt::Result {
+    let mut stack: Vec<Members<'_>> = Vec::new();
+    let mut v = v;
+    loop {
+        // an array or object was opened, so no comma comes before the next member.
+        let mut opened = false;
+        match v {
+            Value::Null => w.write_str("null")?,
+            Value::Boolean(b) => w.write_str(if *b { "true" } else { "false" })?,
+            Value::Number(n) => write!(w, "{}", n)?,
+            Value::String(s) => write_string(w, s)?,
+            Value::Array(arr) if arr.is_empty() => w.write_str("[]")?,
+            Value::Object(obj) if obj.is_empty() => w.write_str("{}")?,
+            Value::Array(arr) => {
+                w.write_char('[')?;
+                stack.push(Members::Array(arr.iter()));
+                opened = true;
+            }
+            Value::Object(obj) if pretty => {
+                let mut members: Vec<_> = obj.iter().collect();
+                members.sort_by(|a, b| a.0.cmp(b.0));
+                w.write_char('{')?;
+                stack.push(Members::Sorted(members.into_iter()));
+                opened = true;
+            }
+            Value::Object(obj) => {
+                w.write_char('{')?;
+                stack.push(Members::Object(obj.iter()));
+                opened = true;
+            }
+        }
+        // on to the next member, closing the arrays and objects which have none left.
+        v = loop {
+            let level = stack.len();
+            let members = match stack.last_mut() {
+                Some(members) => members,
+                None => return Ok(()),
+            };
+            match members.next() {
+                Some((key, v)) => {
+                    if !opened {
+                        w.write_char(',')?;
+                    }
+                    if pretty {
+                        write_indent(w, level)?;
+                    }
+                    if let Some(k) = key {
+                        write_string(w, k)?;
+                        w.write_str(if pretty { ": " } else { ":" })?;
+                    }
+                    break v;
+                }
+                None => {
+                    let close = members.close();
+                    stack.pop();
+                    if pretty {
+                        write_indent(w, level - 1)?;
+                    }
+                    w.write_char(close)?;
+                    opened = false;
+                }
+            }
+        };
+    }
+}
+
+fn write_indent<W: Write>(w: &mut W, level: usize) -> fmt::Result {
+    w.write_char('\n')?;
+    for _ in 0..level {
+        w.write_str("  ")?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_string<W: Write>(w: &mut W, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\x08' => w.write_str("\\b")?,
+            '\x0C' => w.write_str("\\f")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::collections::hash_map::{self, HashMap};
+use std::mem;
+use std::slice;
 
-#[derive(Clone, Debug, PartialEq)]
+use super::number::Number;
+
+// dropping, cloning and comparing walk the nesting with a stack on the heap, as the parser
+// does, so a value nested as deep as Limits::max_depth allows can't overflow the call stack.
+#[derive(Debug)]
 pub enum Value {
     Null,
-    Number(f64),
+    Number(Number),
     Boolean(bool),
     String(String),
     Array(Vec<Value>),
@@ -11,6 +17,7 @@ pub enum Value {
 }
 
 impl Value {
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_null(&self) -> bool {
         match self {
             Value::Null => true,
@@ -18,6 +25,7 @@ impl Value {
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_number(&self) -> bool {
         match self {
             Value::Number(_) => true,
@@ -25,6 +33,7 @@ impl Value {
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_boolean(&self) -> bool {
         match self {
             Value::Boolean(_) => true,
@@ -32,6 +41,7 @@ impl Value {
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_string(&self) -> bool {
         match self {
             Value::String(_) => true,
@@ -39,6 +49,7 @@ impl Value {
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_array(&self) -> bool {
         match self {
             Value::Array(_) => true,
@@ -46,6 +57,7 @@ impl Value {
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_object(&self) -> bool {
         match self {
             Value::Object(_) => true,
@@ -53,13 +65,25 @@ impl Value {
         }
     }
 
-    pub fn as_number(&self) -> Option<f64> {
+    pub fn as_number(&self) -> Option<&Number> {
         match self {
-            Value::Number(v) => Some(*v),
+            Value::Number(v) => Some(v),
             _ => None,
         }
     }
 
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|n| n.as_i64())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|n| n.as_u64())
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().and_then(|n| n.as_f64())
+    }
+
     pub fn as_boolean(&self) -> Option<bool> {
         match self {
             Value::Boolean(v) => Some(*v),
@@ -87,4 +111,141 @@ impl Value {
             _ => None,
         }
     }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object().and_then(|obj| obj.get(key))
+    }
+
+    // name of the JSON type, for error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Number(_) => "number",
+            Value::Boolean(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn is_container(&self) -> bool {
+        matches!(self, Value::Array(_) | Value::Object(_))
+    }
+}
+
+impl Drop for Value {
+    // the members are moved out onto the stack before their container is dropped. a value
+    // without nested containers is left to the usual drop.
+    fn drop(&mut self) {
+        let mut stack = match self {
+            Value::Array(arr) if arr.iter().any(Value::is_container) => mem::take(arr),
+            Value::Object(obj) if obj.values().any(Value::is_container) => {
+                obj.drain().map(|(_, v)| v).collect()
+            }
+            _ => return,
+        };
+        while let Some(mut v) = stack.pop() {
+            match &mut v {
+                Value::Array(arr) => stack.append(arr),
+                Value::Object(obj) => stack.extend(obj.drain().map(|(_, v)| v)),
+                _ => {}
+            }
+        }
+    }
+}
+
+// an array or object being cloned, with the members left to clone.
+enum CloneFrame<'a> {
+    Array(Vec<Value>, slice::Iter<'a, Value>),
+    // members so far, and the key of the member being cloned.
+    Object(
+        HashMap<String, Value>,
+        hash_map::Iter<'a, String, Value>,
+        &'a str,
+    ),
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        let mut stack: Vec<CloneFrame<'_>> = Vec::new();
+        let mut v = self;
+        loop {
+            let mut done = match v {
+                Value::Null => Some(Value::Null),
+                Value::Number(n) => Some(Value::Number(n.clone())),
+                Value::Boolean(b) => Some(Value::Boolean(*b)),
+                Value::String(s) => Some(Value::String(s.clone())),
+                Value::Array(arr) => {
+                    stack.push(CloneFrame::Array(Vec::with_capacity(arr.len()), arr.iter()));
+                    None
+                }
+                Value::Object(obj) => {
+                    let members = HashMap::with_capacity(obj.len());
+                    stack.push(CloneFrame::Object(members, obj.iter(), ""));
+                    None
+                }
+            };
+            // adds a cloned value to its container, and closes the containers which have no
+            // members left, up to one which has.
+            v = loop {
+                let next = match stack.last_mut() {
+                    None => return done.unwrap(),
+                    Some(CloneFrame::Array(arr, iter)) => {
+                        arr.extend(done.take());
+                        iter.next()
+                    }
+                    Some(CloneFrame::Object(obj, iter, key)) => {
+                        if let Some(v) = done.take() {
+                            obj.insert(key.to_string(), v);
+                        }
+                        iter.next().map(|(k, v)| {
+                            *key = k.as_str();
+                            v
+                        })
+                    }
+                };
+                match next {
+                    Some(v) => break v,
+                    None => {
+                        done = Some(match stack.pop() {
+                            Some(CloneFrame::Array(arr, _)) => Value::Array(arr),
+                            Some(CloneFrame::Object(obj, ..)) => Value::Object(Box::new(obj)),
+                            None => unreachable!(),
+                        })
+                    }
+                }
+            };
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        let mut stack = Vec::new();
+        let mut pair = (self, other);
+        loop {
+            match pair {
+                (Value::Null, Value::Null) => {}
+                (Value::Number(a), Value::Number(b)) if a == b => {}
+                (Value::Boolean(a), Value::Boolean(b)) if a == b => {}
+                (Value::String(a), Value::String(b)) if a == b => {}
+                (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+                    stack.extend(a.iter().zip(b.iter()))
+                }
+                (Value::Object(a), Value::Object(b)) if a.len() == b.len() => {
+                    for (k, v) in a.iter() {
+                        match b.get(k) {
+                            Some(w) => stack.push((v, w)),
+                            None => return false,
+                        }
+                    }
+                }
+                _ => return false,
+            }
+            match stack.pop() {
+                Some(next) => pair = next,
+                None => return true,
+            }
+        }
+    }
 }
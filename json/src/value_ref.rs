@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+use std::mem;
+
+use super::number::Number;
+use super::parser::Tree;
+use super::value::Value;
+
+// a value borrowing its strings from the parsed input. strings with escapes are owned.
+// object members keep the input order, and later duplicates win in get().
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueRef<'a> {
+    Null,
+    Number(Number),
+    Boolean(bool),
+    String(Cow<'a, str>),
+    Array(Vec<ValueRef<'a>>),
+    Object(Vec<(Cow<'a, str>, ValueRef<'a>)>),
+}
+
+impl<'a> ValueRef<'a> {
+    pub fn is_null(&self) -> bool {
+        matches!(self, ValueRef::Null)
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, ValueRef::Number(_))
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, ValueRef::Boolean(_))
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, ValueRef::String(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, ValueRef::Array(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, ValueRef::Object(_))
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            ValueRef::Number(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|n| n.as_i64())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|n| n.as_u64())
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().and_then(|n| n.as_f64())
+    }
+
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            ValueRef::Boolean(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ValueRef::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<ValueRef<'a>>> {
+        match self {
+            ValueRef::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Vec<(Cow<'a, str>, ValueRef<'a>)>> {
+        match self {
+            ValueRef::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ValueRef<'a>> {
+        self.as_object()
+            .and_then(|obj| obj.iter().rev().find(|(k, _)| k == key))
+            .map(|(_, v)| v)
+    }
+}
+
+impl<'a> Tree<'a> for ValueRef<'a> {
+    fn null() -> Self {
+        ValueRef::Null
+    }
+
+    fn boolean(b: bool) -> Self {
+        ValueRef::Boolean(b)
+    }
+
+    fn number(n: Number) -> Self {
+        ValueRef::Number(n)
+    }
+
+    fn string(s: Cow<'a, str>) -> Self {
+        ValueRef::String(s)
+    }
+
+    fn array(arr: Vec<Self>) -> Self {
+        ValueRef::Array(arr)
+    }
+
+    fn object(members: Vec<(Cow<'a, str>, Self)>) -> Self {
+        ValueRef::Object(members)
+    }
+}
+
+impl<'a> From<ValueRef<'a>> for Value {
+    fn from(mut v: ValueRef<'a>) -> Self {
+        match &mut v {
+            ValueRef::Null => Value::Null,
+            ValueRef::Number(n) => Value::Number(n.clone()),
+            ValueRef::Boolean(b) => Value::Boolean(*b),
+            ValueRef::String(s) => Value::String(mem::take(s).into_owned()),
+            ValueRef::Array(arr) => Value::Array(arr.drain(..).map(Value::from).collect()),
+            ValueRef::Object(members) => Value::Object(Box::new(
+                members
+                    .drain(..)
+                    .map(|(k, v)| (k.into_owned(), Value::from(v)))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+// dropped through a stack on the heap, the same as Value.
+impl<'a> Drop for ValueRef<'a> {
+    fn drop(&mut self) {
+        let nested = |v: &ValueRef<'a>| v.is_array() || v.is_object();
+        let mut stack = match self {
+            ValueRef::Array(arr) if arr.iter().any(nested) => mem::take(arr),
+            ValueRef::Object(members) if members.iter().any(|(_, v)| nested(v)) => {
+                members.drain(..).map(|(_, v)| v).collect()
+            }
+            _ => return,
+        };
+        while let Some(mut v) = stack.pop() {
+            match &mut v {
+                ValueRef::Array(arr) => stack.append(arr),
+                ValueRef::Object(members) => stack.extend(members.drain(..).map(|(_, v)| v)),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use json::{cbor, msgpack, value, ErrKind, Limits, Value};
+use std::str::FromStr;
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+const DOCUMENTS: &[&str] = &[
+    "null",
+    "true",
+    "[false, 0, 23, 24, 255, 256, 65535, 65536, 4294967296, 18446744073709551615]",
+    "[-1, -24, -25, -32, -33, -128, -129, -32768, -32769, -2147483649, -9223372036854775808]",
+    "[0.5, -1.1, 1e300, 3.4028234663852886e38]",
+    r#"["", "a", "日本語", "\u0000", "0123456789012345678901234567890123456789"]"#,
+    r#"{"a": {"b": [[], {}]}, "": null}"#,
+];
+
+#[test]
+fn test_round_trip() {
+    let long = format!(
+        r#"["{}", {}]"#,
+        "x".repeat(70_000),
+        Value::from(vec![1; 70_000])
+    );
+    let mut docs: Vec<&str> = DOCUMENTS.to_vec();
+    docs.push(&long);
+    for doc in docs {
+        let v = Value::from_str(doc).unwrap();
+        assert_eq!(
+            Ok(v.clone()),
+            cbor::from_slice(&cbor::to_vec(&v)),
+            "{}",
+            doc
+        );
+        assert_eq!(
+            Ok(v.clone()),
+            msgpack::from_slice(&msgpack::to_vec(&v)),
+            "{}",
+            doc
+        );
+    }
+}
+
+#[test]
+fn test_cbor() {
+    // from RFC 8949 appendix A.
+    let cases = vec![
+        ("00", "0"),
+        ("17", "23"),
+        ("1818", "24"),
+        ("1903e8", "1000"),
+        ("1bffffffffffffffff", "18446744073709551615"),
+        ("20", "-1"),
+        ("3863", "-100"),
+        ("f90000", "0.0"),
+        ("f93c00", "1.0"),
+        ("f9c400", "-4.0"),
+        ("f97bff", "65504.0"),
+        ("f90001", "5.960464477539063e-8"),
+        ("fa47c35000", "100000.0"),
+        ("fb3ff199999999999a", "1.1"),
+        ("f4", "false"),
+        ("f6", "null"),
+        ("60", r#""""#),
+        ("6449455446", r#""IETF""#),
+        ("62225c", r#""\"\\""#),
+        ("83010203", "[1, 2, 3]"),
+        ("8301820203820405", "[1, [2, 3], [4, 5]]"),
+        ("a26161016162820203", r#"{"a": 1, "b": [2, 3]}"#),
+        ("7f657374726561646d696e67ff", r#""streaming""#),
+        ("9f018202039f0405ffff", "[1, [2, 3], [4, 5]]"),
+        ("bf6346756ef563416d7421ff", r#"{"Fun": true, "Amt": -2}"#),
+    ];
+    for case in cases {
+        let expected = Value::from_str(case.1).unwrap();
+        assert_eq!(Ok(expected), cbor::from_slice(&hex(case.0)), "{}", case.0);
+    }
+    assert_eq!(hex("a16161820102"), cbor::to_vec(&value!({"a": [1, 2]})));
+    // below i64::MIN, read the same as the text "-18446744073709551616".
+    assert_eq!(
+        Value::from_str("-18446744073709551616"),
+        cbor::from_slice(&hex("3bffffffffffffffff"))
+    );
+    assert!(cbor::from_slice(&hex("f97e00"))
+        .unwrap()
+        .as_f64()
+        .unwrap()
+        .is_nan());
+}
+
+#[test]
+fn test_msgpack() {
+    let cases = vec![
+        ("c0", "null"),
+        ("c3", "true"),
+        ("7f", "127"),
+        ("e0", "-32"),
+        ("cc80", "128"),
+        ("d0df", "-33"),
+        ("cdffff", "65535"),
+        ("d3ffffffffffffffff", "-1"),
+        ("ca3fc00000", "1.5"),
+        ("cb3ff199999999999a", "1.1"),
+        ("a3616263", r#""abc""#),
+        ("d903616263", r#""abc""#),
+        ("92c2c0", "[false, null]"),
+        ("dc000101", "[1]"),
+        ("81a161dd0000000190", r#"{"a": [[]]}"#),
+    ];
+    for case in cases {
+        let expected = Value::from_str(case.1).unwrap();
+        assert_eq!(
+            Ok(expected),
+            msgpack::from_slice(&hex(case.0)),
+            "{}",
+            case.0
+        );
+    }
+    assert_eq!(
+        hex("81a16192cc80ff"),
+        msgpack::to_vec(&value!({"a": [128, -1]}))
+    );
+}
+
+#[test]
+fn test_unsupported() {
+    let cases = vec![
+        (cbor::from_slice(&hex("4401020304")), "byte string"),
+        (cbor::from_slice(&hex("c11a514b67b0")), "tag"),
+        (cbor::from_slice(&hex("f7")), "undefined"),
+        (cbor::from_slice(&hex("f0")), "simple value"),
+        (cbor::from_slice(&hex("a10102")), "non-string map key"),
+        (msgpack::from_slice(&hex("c40100")), "binary"),
+        (msgpack::from_slice(&hex("d6ffffffffff")), "extension type"),
+        (msgpack::from_slice(&hex("810102")), "non-string map key"),
+    ];
+    for case in cases {
+        let err = case.0.unwrap_err();
+        assert_eq!(&ErrKind::Unsupported(case.1), err.kind());
+        assert_eq!(
+            format!("{} has no JSON equivalent", case.1),
+            err.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_invalid() {
+    let cbor_cases = vec![
+        "", "18", "1a0000", "62", "6261", "62ff00", "830102", "ff", "0000", "9f01", "5f",
+    ];
+    for case in cbor_cases {
+        assert!(cbor::from_slice(&hex(case)).is_err(), "{}", case);
+    }
+    let msgpack_cases = vec![
+        "",
+        "c1",
+        "cd00",
+        "a261",
+        "a2fffe",
+        "9201",
+        "dbffffffff",
+        "c0c0",
+    ];
+    for case in msgpack_cases {
+        assert!(msgpack::from_slice(&hex(case)).is_err(), "{}", case);
+    }
+
+    let deep = vec![0x81; 1000];
+    let err = cbor::from_slice(&deep).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+    let deep = vec![0x91; 1000];
+    let err = msgpack::from_slice(&deep).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+}
+
+type Decode = fn(&[u8], Limits) -> Result<Value, json::Err>;
+
+#[test]
+fn test_limits() {
+    let v = value!([[[1]], {"abc": "defg", "h": 1}]);
+    let decoders: Vec<(Vec<u8>, Decode)> = vec![
+        (cbor::to_vec(&v), cbor::from_slice_with_limits),
+        (msgpack::to_vec(&v), msgpack::from_slice_with_limits),
+    ];
+    for (bs, decode) in decoders {
+        let limits = Limits {
+            max_depth: 3,
+            max_string_length: 4,
+            max_object_members: 2,
+            max_input_size: bs.len(),
+        };
+        assert_eq!(Ok(v.clone()), decode(&bs, limits.clone()));
+        let cases = vec![
+            (
+                Limits {
+                    max_depth: 2,
+                    ..limits.clone()
+                },
+                ErrKind::DepthLimitExceeded,
+            ),
+            (
+                Limits {
+                    max_string_length: 3,
+                    ..limits.clone()
+                },
+                ErrKind::StringTooLong,
+            ),
+            (
+                Limits {
+                    max_object_members: 1,
+                    ..limits.clone()
+                },
+                ErrKind::TooManyMembers,
+            ),
+            (
+                Limits {
+                    max_input_size: bs.len() - 1,
+                    ..limits.clone()
+                },
+                ErrKind::InputTooLarge,
+            ),
+        ];
+        for (limits, kind) in cases {
+            assert_eq!(&kind, decode(&bs, limits).unwrap_err().kind());
+        }
+    }
+
+    // far deeper than the default limit of 128. the decoders don't recurse.
+    let limits = Limits {
+        max_depth: 10_000,
+        ..Limits::default()
+    };
+    let cbor_cases = vec![
+        [&[0x81; 10_000][..], &[0xF6]].concat(),
+        [&[0x9F; 10_000][..], &[0xFF; 10_000]].concat(),
+        [&[0xA1, 0x61, b'a'].repeat(10_000)[..], &[0xF6]].concat(),
+    ];
+    for bs in cbor_cases {
+        assert!(cbor::from_slice_with_limits(&bs, limits.clone()).is_ok());
+        let err = cbor::from_slice_with_limits(&[&[0x81][..], &bs].concat(), limits.clone());
+        assert_eq!(&ErrKind::DepthLimitExceeded, err.unwrap_err().kind());
+    }
+    let msgpack_cases = vec![
+        [&[0x91; 10_000][..], &[0xC0]].concat(),
+        [&[0x81, 0xA1, b'a'].repeat(10_000)[..], &[0xC0]].concat(),
+    ];
+    for bs in msgpack_cases {
+        assert!(msgpack::from_slice_with_limits(&bs, limits.clone()).is_ok());
+        let err = msgpack::from_slice_with_limits(&[&[0x91][..], &bs].concat(), limits.clone());
+        assert_eq!(&ErrKind::DepthLimitExceeded, err.unwrap_err().kind());
+    }
+    // indefinite-length maps are counted as they are read.
+    let limits = Limits {
+        max_object_members: 1,
+        ..Limits::default()
+    };
+    let err = cbor::from_slice_with_limits(&hex("bf616101616202ff"), limits).unwrap_err();
+    assert_eq!(&ErrKind::TooManyMembers, err.kind());
+}
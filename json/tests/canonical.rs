@@ -0,0 +1,90 @@
+use json::{canonical, value, Value};
+use std::str::FromStr;
+
+#[test]
+fn test_to_string() {
+    // from RFC 8785, section 3.2.2.
+    let v = Value::from_str(
+        r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "€$\u000F\u000aA'B\u0022\u005c\\\u0022\/",
+            "literals": [null, true, false]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#,
+        canonical::to_string(&v).unwrap()
+    );
+}
+
+#[test]
+fn test_sort() {
+    // from RFC 8785, section 3.2.3.
+    let v = Value::from_str(
+        r#"{
+            "€": "Euro Sign",
+            "\r": "Carriage Return",
+            "\ufb33": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "😀": "Emoji: Grinning Face",
+            "\u0080": "Control",
+            "ö": "Latin Small Letter O With Diaeresis"
+        }"#,
+    )
+    .unwrap();
+    let expected = concat!(
+        r#"{"\r":"Carriage Return","1":"One","#,
+        "\"\u{80}\":\"Control\",",
+        r#""ö":"Latin Small Letter O With Diaeresis","€":"Euro Sign","#,
+        r#""😀":"Emoji: Grinning Face","#,
+        "\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+    );
+    assert_eq!(expected, canonical::to_string(&v).unwrap());
+}
+
+#[test]
+fn test_numbers() {
+    let cases = vec![
+        (value!(0), "0"),
+        (value!(-0.0), "0"),
+        (value!(1), "1"),
+        (value!(-1.5), "-1.5"),
+        (value!(1e21), "1e+21"),
+        (value!(1e20), "100000000000000000000"),
+        (value!(123456789012345680000.0), "123456789012345680000"),
+        (value!(0.000001), "0.000001"),
+        (value!(0.0000001), "1e-7"),
+        (value!(1.2345e-7), "1.2345e-7"),
+        (value!(5e-324), "5e-324"),
+        (value!(f64::MAX), "1.7976931348623157e+308"),
+        (value!(9007199254740993u64), "9007199254740992"),
+        (value!(u64::MAX), "18446744073709552000"),
+        (value!(i64::MIN), "-9223372036854776000"),
+    ];
+    for case in cases {
+        assert_eq!(case.1, canonical::to_string(&case.0).unwrap());
+    }
+    assert!(canonical::to_string(&value!([f64::NAN])).is_err());
+    assert!(canonical::to_string(&value!({"a": f64::INFINITY})).is_err());
+}
+
+#[test]
+fn test_hash() {
+    let a = Value::from_str(r#"{"b": [1.0, "x"], "a": {"d": null, "c": 1e2}}"#).unwrap();
+    let b = Value::from_str(r#"{ "a": {"c": 100, "d": null}, "b": [1, "x"] }"#).unwrap();
+    assert_eq!(canonical::hash(&a).unwrap(), canonical::hash(&b).unwrap());
+    assert_ne!(
+        canonical::hash(&a).unwrap(),
+        canonical::hash(&value!({})).unwrap()
+    );
+
+    // SHA-256 of "{}".
+    let expected = "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a";
+    let hex: String = canonical::hash(&value!({}))
+        .unwrap()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(expected, hex);
+}
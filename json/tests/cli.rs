@@ -0,0 +1,88 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// runs the binary with the input on stdin, and returns the exit code, stdout and stderr.
+fn run(args: &[&str], input: &str) -> (i32, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_json"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the binary may exit before reading all of its input.
+    match child.stdin.take().unwrap().write_all(input.as_bytes()) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => (),
+        r => r.unwrap(),
+    }
+    let out = child.wait_with_output().unwrap();
+    (
+        out.status.code().unwrap(),
+        String::from_utf8(out.stdout).unwrap(),
+        String::from_utf8(out.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!((0, String::new(), String::new()), run(&["validate"], "[1]"));
+
+    let (code, _, err) = run(&["validate"], "{\"a\":\n  [1,,2]}");
+    assert_eq!(1, code);
+    assert_eq!("-: syntax error at line 2 column 6\n", err);
+
+    let (code, _, _) = run(&["validate", "--json5"], "{a: [1, 2,]}");
+    assert_eq!(0, code);
+}
+
+#[test]
+fn test_format() {
+    let input = r#"{"b": [1, {"c": null}], "a": "x", "d": {}}"#;
+    let pretty = "{\n  \"a\": \"x\",\n  \"b\": [\n    1,\n    {\n      \"c\": null\n    }\n  ],\n  \"d\": {}\n}\n";
+    assert_eq!(
+        (0, pretty.to_string(), String::new()),
+        run(&["pretty"], input)
+    );
+
+    let (code, out, _) = run(&["minify"], "[ 1 , [ ] , \"a b\" ]");
+    assert_eq!((0, "[1,[],\"a b\"]\n"), (code, out.as_str()));
+}
+
+#[test]
+fn test_query() {
+    let input = r#"{"users": [{"name": "a", "tags": ["x"]}, {"name": "b"}]}"#;
+    let (_, out, _) = run(&["query", "/users/1/name"], input);
+    assert_eq!("\"b\"\n", out);
+    let (_, out, _) = run(&["query", "-c", "$.users[*].name"], input);
+    assert_eq!("\"a\"\n\"b\"\n", out);
+    let (_, out, _) = run(&["query", "/users/0/tags"], input);
+    assert_eq!("[\n  \"x\"\n]\n", out);
+    let (code, out, _) = run(&["query", "/none"], input);
+    assert_eq!((0, ""), (code, out.as_str()));
+
+    let (code, _, err) = run(&["query", "users"], input);
+    assert_eq!((2, "json: invalid path: users\n"), (code, err.as_str()));
+}
+
+#[test]
+fn test_ndjson() {
+    let (code, out, _) = run(&["to-array"], "1\n\n{\"a\":[2]}\n");
+    assert_eq!((0, "[1,{\"a\":[2]}]\n"), (code, out.as_str()));
+    let (code, out, _) = run(&["to-array", "--json5"], "{a: 'b',}\n+1\n");
+    assert_eq!((0, "[{\"a\":\"b\"},1]\n"), (code, out.as_str()));
+    let (code, _, err) = run(&["to-array"], "1\n{\"a\" 2}\n");
+    assert_eq!(
+        (1, "-: syntax error at line 2 column 6\n"),
+        (code, err.as_str())
+    );
+
+    let (code, out, _) = run(&["to-ndjson"], "[1, \"a\\nb\", [null]]");
+    assert_eq!((0, "1\n\"a\\nb\"\n[null]\n"), (code, out.as_str()));
+    let (code, _, err) = run(&["to-ndjson"], "{}");
+    assert_eq!(
+        (1, "-: expected array, found object\n"),
+        (code, err.as_str())
+    );
+}
@@ -0,0 +1,127 @@
+use json::{value, Err, FromJson, ToJson, Value};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq)]
+struct User {
+    name: String,
+    age: u8,
+    email: Option<String>,
+}
+
+impl<'a> FromJson<'a> for User {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        Ok(User {
+            name: v.field("name")?,
+            age: v.field("age")?,
+            email: v.field("email")?,
+        })
+    }
+}
+
+impl ToJson for User {
+    fn to_json(&self) -> Value {
+        value!({"name": self.name.to_json(), "age": self.age, "email": self.email.to_json()})
+    }
+}
+
+#[test]
+fn test_struct() {
+    let v = value!({"users": [{"name": "a", "age": 20}, {"name": "b", "age": 30, "email": "b@x"}]});
+    let users: HashMap<String, Vec<User>> = FromJson::from_json(&v).unwrap();
+    let users = &users["users"];
+    assert_eq!(None, users[0].email);
+    assert_eq!(Some("b@x".to_string()), users[1].email);
+    assert_eq!(
+        value!([{"name": "a", "age": 20, "email": null}, {"name": "b", "age": 30, "email": "b@x"}]),
+        users.to_json()
+    );
+
+    let v = value!({"users": [{"name": "a", "age": 20}, {"name": "b", "age": "30"}]});
+    let err = HashMap::<String, Vec<User>>::from_json(&v).unwrap_err();
+    assert_eq!(Some("/users/1/age"), err.path());
+    assert_eq!(
+        "expected number, found string at /users/1/age",
+        err.to_string()
+    );
+
+    let v = value!([{"name": "a", "age": 300}]);
+    let err = Vec::<User>::from_json(&v).unwrap_err();
+    assert_eq!("number out of range for u8 at /0/age", err.to_string());
+
+    let err = Vec::<User>::from_json(&value!([{"age": 1}])).unwrap_err();
+    assert_eq!("missing field `name` at /0", err.to_string());
+    let err = User::from_json(&value!([])).unwrap_err();
+    assert_eq!("expected object, found array", err.to_string());
+}
+
+#[test]
+fn test_primitives() {
+    assert_eq!(Ok(true), bool::from_json(&value!(true)));
+    assert_eq!(Ok(-3), i64::from_json(&value!(-3)));
+    assert_eq!(Ok(1.5), f32::from_json(&value!(1.5)));
+    assert_eq!(Ok("a".to_string()), String::from_json(&value!("a")));
+    assert_eq!(Ok(None), Option::<u8>::from_json(&value!(null)));
+    assert_eq!(
+        "expected u32, found number",
+        u32::from_json(&value!(1.5)).unwrap_err().to_string()
+    );
+    assert!(u32::from_json(&value!(-1)).is_err());
+
+    assert_eq!(value!(1), 1u8.to_json());
+    assert_eq!(value!("a"), "a".to_json());
+    assert_eq!(value!(null), None::<i32>.to_json());
+    assert_eq!(value!([1, 2]), [1, 2][..].to_json());
+}
+
+#[test]
+fn test_collections() {
+    let mut map = BTreeMap::new();
+    map.insert("a".to_string(), vec![(1, "x".to_string())]);
+    let v = map.to_json();
+    assert_eq!(value!({"a": [[1, "x"]]}), v);
+    assert_eq!(Ok(map), BTreeMap::from_json(&v));
+
+    let err = BTreeMap::<String, Vec<(i32, String)>>::from_json(&value!({"a/b": [[1, 2]]}));
+    assert_eq!(Some("/a~1b/0/1"), err.unwrap_err().path());
+
+    let err = <(i32, i32)>::from_json(&value!([1, 2, 3])).unwrap_err();
+    assert_eq!(
+        "expected array of length 2, found array of length 3",
+        err.to_string()
+    );
+    let err = <(i32, i32)>::from_json(&value!({})).unwrap_err();
+    assert_eq!("expected array of length 2, found object", err.to_string());
+    assert_eq!(
+        Ok((1, true, None::<u8>)),
+        FromJson::from_json(&value!([1, true, null]))
+    );
+}
+
+#[test]
+fn test_borrowed() {
+    let v = value!({"name": "a", "tags": ["x", "y"]});
+    let name: &str = v.field("name").unwrap();
+    assert_eq!("a", name);
+    let tags: Vec<&str> = v.field("tags").unwrap();
+    assert_eq!(vec!["x", "y"], tags);
+    assert!(<&str>::from_json(&value!(1)).is_err());
+}
+
+#[test]
+fn test_arrays() {
+    let v = [1u8, 2, 3, 4].to_json();
+    assert_eq!(value!([1, 2, 3, 4]), v);
+    assert_eq!(Ok([1u8, 2, 3, 4]), FromJson::from_json(&v));
+    assert_eq!(
+        "expected array of length 2, found array of length 4",
+        <[u8; 2]>::from_json(&v).unwrap_err().to_string()
+    );
+    assert_eq!(
+        "number out of range for u8 at /1",
+        <[u8; 2]>::from_json(&value!([1, 256]))
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(Ok(Box::new(1)), Box::<i32>::from_json(&value!(1)));
+    assert_eq!(value!("a"), Box::new("a").to_json());
+}
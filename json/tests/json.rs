@@ -1,3 +1,4 @@
+#[allow(clippy::single_component_path_imports)]
 use json;
 use std::str::FromStr;
 
@@ -34,6 +35,7 @@ fn test_string() {
 }
 
 #[test]
+#[allow(clippy::unnecessary_cast)]
 fn test_number() {
     let cases: Vec<(&str, f64)> = vec![
         ("123456789", 123456789 as f64),
@@ -104,3 +106,207 @@ fn test_spaces() {
     // TODO: error test
     assert_ne!(Ok(json::Value::Null), json::Value::from_str("  null 1"));
 }
+
+#[test]
+fn test_integer() {
+    let v = json::Value::from_str("18446744073709551615").unwrap();
+    assert_eq!(Some(u64::MAX), v.as_u64());
+    assert_eq!(None, v.as_i64());
+
+    let v = json::Value::from_str("-9223372036854775808").unwrap();
+    assert_eq!(Some(i64::MIN), v.as_i64());
+    assert_eq!(None, v.as_u64());
+
+    let v = json::Value::from_str("9007199254740993").unwrap();
+    assert_eq!(Some(9007199254740993), v.as_i64());
+    assert_ne!(json::value!(9007199254740992u64), v);
+
+    let v = json::Value::from_str("1.5").unwrap();
+    assert_eq!(None, v.as_i64());
+    assert_eq!(Some(1.5), v.as_f64());
+    assert_eq!(json::value!(1), json::Value::from_str("1.0").unwrap());
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn test_arbitrary_precision() {
+    let cases = vec!["123456789012345678901234567890", "1.10", "-0.0", "1E+2"];
+    for case in cases {
+        let v = json::Value::from_str(case).unwrap();
+        assert_eq!(Some(case), v.as_number().unwrap().as_str());
+        assert_eq!(case, v.to_string());
+    }
+}
+
+#[test]
+fn test_to_string() {
+    let cases = vec![
+        (json::value!(null), "null"),
+        (json::value!(true), "true"),
+        (json::value!(123), "123"),
+        (json::value!(-123), "-123"),
+        (
+            json::value!(18446744073709551615u64),
+            "18446744073709551615",
+        ),
+        (json::value!(1.5), "1.5"),
+        (json::value!(1.0), "1.0"),
+        (json::value!(f64::NAN), "null"),
+        (json::value!("a\"\\\n\u{1}"), r#""a\"\\\n\u0001""#),
+        (json::value!([1, "b", [null]]), r#"[1,"b",[null]]"#),
+        (json::value!({"a": {"b": 2}}), r#"{"a":{"b":2}}"#),
+    ];
+    for case in cases {
+        assert_eq!(case.1, case.0.to_string());
+    }
+}
+
+#[test]
+fn test_empty() {
+    assert_eq!(Ok(json::value!([])), json::Value::from_str(" [ ] "));
+    assert_eq!(Ok(json::value!({})), json::Value::from_str(" { } "));
+    assert_eq!(Ok(json::value!([[], {}])), json::Value::from_str("[[],{}]"));
+}
+
+#[test]
+fn test_invalid() {
+    let cases = vec![
+        "",
+        "-",
+        "1.2.3",
+        "01",
+        "1.",
+        "1e",
+        "[1,]",
+        "{\"a\":1,}",
+        "\"\t\"",
+        "\"\\ud800\"",
+        "\"\\udc00\\ud800\"",
+    ];
+    for case in cases {
+        assert!(json::Value::from_str(case).is_err(), "{}", case);
+    }
+    #[cfg(not(feature = "arbitrary_precision"))]
+    assert!(json::Value::from_str("[1e400]").is_err());
+    assert_eq!(Ok(json::value!(1e5)), json::Value::from_str("1e5"));
+    assert_eq!(
+        Ok(json::value!("😀")),
+        json::Value::from_str(r#""\ud83d\ude00""#)
+    );
+}
+
+#[test]
+fn test_macro() {
+    let key = "k".to_string();
+    let n = 3;
+    let v = json::value!({
+        "a": [1, -2, -3.5, true, false, null, {}, [], {"b": [{}]},],
+        key.clone(): n * 2,
+        (format!("{}2", key)): -n,
+        "nested": {"t": true, "f": false, "n": null, "e": "x".repeat(2),},
+    });
+    let expected = json::Value::from_str(
+        r#"{
+            "a": [1, -2, -3.5, true, false, null, {}, [], {"b": [{}]}],
+            "k": 6,
+            "k2": -3,
+            "nested": {"t": true, "f": false, "n": null, "e": "xx"}
+        }"#,
+    );
+    assert_eq!(Ok(v), expected);
+
+    assert_eq!(
+        json::value!([[{}], [[]],]),
+        json::Value::from_str("[[{}],[[]]]").unwrap()
+    );
+    assert_eq!(json::value!([(-1), -1, 2 - 3]), json::value!([-1, -1, -1]));
+    assert_eq!(json::value!({1: 1}), json::value!({"1": 1}));
+}
+
+#[test]
+fn test_from_collections() {
+    use std::collections::HashMap;
+
+    assert_eq!(json::value!([1, 2]), json::Value::from(vec![1, 2]));
+    assert_eq!(json::value!(null), json::Value::from(None::<i32>));
+    assert_eq!(json::value!("a"), json::Value::from(Some("a")));
+    assert_eq!(
+        json::value!([[1], null]),
+        json::Value::from(vec![Some(vec![1]), None])
+    );
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), vec![true]);
+    assert_eq!(json::value!({"a": [true]}), json::Value::from(map));
+
+    let v: json::Value = (1..4).collect();
+    assert_eq!(json::value!([1, 2, 3]), v);
+    let v: json::Value = vec![("x".to_string(), 1), ("y".to_string(), 2)]
+        .into_iter()
+        .collect();
+    assert_eq!(json::value!({"x": 1, "y": 2}), v);
+}
+
+#[test]
+fn test_try_from() {
+    use json::ErrKind;
+    use std::convert::TryFrom;
+
+    assert_eq!(Ok(true), bool::try_from(json::value!(true)));
+    assert_eq!(Ok("a".to_string()), String::try_from(json::value!("a")));
+    assert_eq!(Ok(-5), i8::try_from(json::value!(-5)));
+    assert_eq!(Ok(u64::MAX), u64::try_from(json::value!(u64::MAX)));
+    assert_eq!(Ok(1.5), f64::try_from(json::value!(1.5)));
+    assert_eq!(Ok(2.0), f32::try_from(json::value!(2)));
+
+    let err = i32::try_from(json::value!("1")).unwrap_err();
+    assert_eq!(
+        &ErrKind::Type {
+            expected: "number",
+            found: "string"
+        },
+        err.kind()
+    );
+    assert_eq!("expected number, found string", err.to_string());
+    assert_eq!(
+        Err(err),
+        <i32 as json::FromJson>::from_json(&json::value!("1"))
+    );
+
+    let err = u8::try_from(json::value!(256)).unwrap_err();
+    assert_eq!(&ErrKind::OutOfRange("u8"), err.kind());
+    assert_eq!("number out of range for u8", err.to_string());
+    assert!(u32::try_from(json::value!(-1)).is_err());
+    assert!(i64::try_from(json::value!(1.5)).is_err());
+    assert!(f32::try_from(json::value!(1e300)).is_err());
+    assert!(bool::try_from(json::value!(null)).is_err());
+}
+
+#[test]
+fn test_error_position() {
+    let cases = vec![
+        ("[1,]", 1, 4),
+        ("{\"a\": 1,\n \"b\" 2}", 2, 6),
+        ("[\"日本\", x]", 1, 8),
+        ("\n\n  nul", 3, 3),
+    ];
+    for case in cases {
+        let err = json::Value::from_str(case.0).unwrap_err();
+        assert_eq!(
+            (Some(case.1), Some(case.2)),
+            (err.line(), err.column()),
+            "{}",
+            case.0
+        );
+    }
+}
+
+#[test]
+fn test_to_string_pretty() {
+    let v = json::value!({"b": [1, [], {}], "a": {"c": "d"}});
+    assert_eq!(
+        "{\n  \"a\": {\n    \"c\": \"d\"\n  },\n  \"b\": [\n    1,\n    [],\n    {}\n  ]\n}",
+        v.to_string_pretty()
+    );
+    assert_eq!("1", json::value!(1).to_string_pretty());
+}
@@ -0,0 +1,118 @@
+use json::{value, ParserOptions};
+use std::str::FromStr;
+
+fn json5(s: &str) -> Result<json::Value, json::Err> {
+    json::from_str_with_options(s, ParserOptions::json5())
+}
+
+#[test]
+fn test_json5() {
+    let v = json5(
+        r#"// config
+        {
+            /* unquoted keys */
+            name: 'app',
+            $version: 0x1F,
+            "mask": -0XfF,
+            'quoted': 'it\'s',
+            list: [1, 2, 3,],
+            nested: {a: null,}, // trailing comma
+        }
+        /* end */"#,
+    );
+    assert_eq!(
+        Ok(value!({
+            "name": "app",
+            "$version": 31,
+            "mask": (-255),
+            "quoted": "it's",
+            "list": [1, 2, 3],
+            "nested": {"a": null}
+        })),
+        v
+    );
+}
+
+#[test]
+fn test_relaxed_numbers() {
+    assert_eq!(
+        Ok(value!([1, 0.5, 5.0, (-0.5), 500.0, 0.5, 31])),
+        json5("[+1, .5, 5., -.5, 5.e2, +.5, +0x1F]")
+    );
+    assert_eq!(Some(f64::INFINITY), json5("+Infinity").unwrap().as_f64());
+    let cases = vec![
+        "+", ".", "-.", "+-1", "..5", "5..", ".e1", "+.", "01", "++1",
+    ];
+    for case in cases {
+        assert!(json5(case).is_err(), "{}", case);
+    }
+}
+
+#[test]
+fn test_line_continuations() {
+    assert_eq!(
+        Ok(value!(["ab", "ab", "ab", "ab", "a\nb"])),
+        json5("['a\\\nb', 'a\\\r\nb', 'a\\\rb', 'a\\\u{2028}b', 'a\\nb']")
+    );
+}
+
+#[test]
+fn test_special_floats() {
+    let v = json5("[Infinity, -Infinity, NaN]").unwrap();
+    let arr = v.as_array().unwrap();
+    assert_eq!(Some(f64::INFINITY), arr[0].as_f64());
+    assert_eq!(Some(f64::NEG_INFINITY), arr[1].as_f64());
+    assert!(arr[2].as_f64().unwrap().is_nan());
+}
+
+#[test]
+fn test_options() {
+    let comments = ParserOptions {
+        comments: true,
+        ..ParserOptions::default()
+    };
+    assert_eq!(
+        Ok(value!([1])),
+        json::from_str_with_options("[1 /* one */] // end", comments.clone())
+    );
+    assert!(json::from_str_with_options("[1,]", comments.clone()).is_err());
+    assert!(json::from_str_with_options("[1] /* open", comments.clone()).is_err());
+    assert!(json::from_str_with_options("[1] / 2", comments).is_err());
+
+    let trailing_commas = ParserOptions {
+        trailing_commas: true,
+        ..ParserOptions::default()
+    };
+    assert_eq!(
+        Ok(value!({"a": [1]})),
+        json::from_str_with_options(r#"{"a": [1,],}"#, trailing_commas.clone())
+    );
+    assert!(json::from_str_with_options("[,]", trailing_commas.clone()).is_err());
+    assert!(json::from_str_with_options("[1,,]", trailing_commas).is_err());
+
+    assert!(json5("0x").is_err());
+    assert!(json5("0x10000000000000000").is_err());
+    assert!(json5("{1a: 1}").is_err());
+    assert!(json5("'unclosed\"").is_err());
+}
+
+#[test]
+fn test_strict_by_default() {
+    let cases = vec![
+        "[1] // comment",
+        "[1,]",
+        "{a: 1}",
+        "'a'",
+        "0x1F",
+        "NaN",
+        "-Infinity",
+        "+1",
+        ".5",
+        "5.",
+        "\"a\\\nb\"",
+    ];
+    for case in cases {
+        assert!(json::Value::from_str(case).is_err(), "{}", case);
+        assert!(json5(case).is_ok(), "{}", case);
+    }
+}
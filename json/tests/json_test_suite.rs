@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::PathBuf;
+
+fn cases(prefix: &str) -> Vec<(String, Vec<u8>)> {
+    let dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/JSONTestSuite/test_parsing");
+    let mut cases: Vec<(String, Vec<u8>)> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            name.starts_with(prefix) && name.ends_with(".json")
+        })
+        .map(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            (name, fs::read(path).unwrap())
+        })
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty());
+    cases
+}
+
+#[test]
+fn test_suite_accepted() {
+    for (name, bs) in cases("y_") {
+        assert!(json::from_slice(&bs).is_ok(), "{} should be accepted", name);
+    }
+}
+
+#[test]
+fn test_suite_rejected() {
+    for (name, bs) in cases("n_") {
+        assert!(
+            json::from_slice(&bs).is_err(),
+            "{} should be rejected",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_suite_implementation_defined() {
+    for (_, bs) in cases("i_") {
+        let _ = json::from_slice(&bs);
+    }
+}
+
+// comments turn the structural index off, so both parsers must agree where there are none.
+#[test]
+fn test_suite_without_index() {
+    let options = json::ParserOptions {
+        comments: true,
+        ..Default::default()
+    };
+    for (name, bs) in cases("") {
+        if bs.contains(&b'/') {
+            continue;
+        }
+        assert_eq!(
+            json::from_slice(&bs),
+            json::from_slice_with_options(&bs, options.clone()),
+            "{}",
+            name
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use json::value;
+use std::str::FromStr;
+
+fn store() -> json::Value {
+    json::Value::from_str(
+        r#"{ "store": {
+            "book": [
+              { "category": "reference", "author": "Nigel Rees",
+                "title": "Sayings of the Century", "price": 8.95 },
+              { "category": "fiction", "author": "Evelyn Waugh",
+                "title": "Sword of Honour", "price": 12.99 },
+              { "category": "fiction", "author": "Herman Melville",
+                "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99 },
+              { "category": "fiction", "author": "J. R. R. Tolkien",
+                "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99 }
+            ],
+            "bicycle": { "color": "red", "price": 399 }
+          }
+        }"#,
+    )
+    .unwrap()
+}
+
+// object members have no order, so results are sorted before comparison.
+fn query(path: &str) -> Vec<json::Value> {
+    sorted(store().query(path).unwrap().into_iter().cloned().collect())
+}
+
+fn sorted(mut vals: Vec<json::Value>) -> Vec<json::Value> {
+    vals.sort_by_key(|v| v.to_string());
+    vals
+}
+
+#[test]
+fn test_child() {
+    assert_eq!(vec![value!("red")], query("$.store.bicycle.color"));
+    assert_eq!(
+        vec![value!("red")],
+        query(r#"$['store']['bicycle']["color"]"#)
+    );
+    assert_eq!(vec![store()], query("$"));
+    assert!(query("$.store.none").is_empty());
+}
+
+#[test]
+fn test_index_and_slice() {
+    let v = store();
+    let titles = |path: &str| -> Vec<json::Value> {
+        v.query(path)
+            .unwrap()
+            .into_iter()
+            .map(|b| b["title"].clone())
+            .collect()
+    };
+    assert_eq!(vec![value!("Sword of Honour")], titles("$.store.book[1]"));
+    assert_eq!(
+        vec![value!("The Lord of the Rings")],
+        titles("$.store.book[-1]")
+    );
+    assert_eq!(
+        vec![value!("Sayings of the Century"), value!("Sword of Honour")],
+        titles("$.store.book[:2]")
+    );
+    assert_eq!(
+        vec![value!("The Lord of the Rings"), value!("Sword of Honour")],
+        titles("$.store.book[::-2]")
+    );
+    assert_eq!(
+        vec![value!("Sayings of the Century"), value!("Moby Dick")],
+        titles("$.store.book[0, 2]")
+    );
+    assert!(titles("$.store.book[1:1]").is_empty());
+    assert!(titles("$.store.book[::0]").is_empty());
+    assert!(titles("$.store.book[9]").is_empty());
+    assert_eq!(
+        vec![value!("Sword of Honour")],
+        titles("$.store.book[1::9223372036854775807]")
+    );
+    assert_eq!(
+        vec![value!("The Lord of the Rings")],
+        titles("$.store.book[::-9223372036854775808]")
+    );
+}
+
+#[test]
+fn test_wildcard_and_descendant() {
+    assert_eq!(
+        sorted(vec![
+            value!("Nigel Rees"),
+            value!("Evelyn Waugh"),
+            value!("Herman Melville"),
+            value!("J. R. R. Tolkien")
+        ]),
+        query("$..author")
+    );
+    assert_eq!(
+        sorted(vec![
+            value!(8.95),
+            value!(12.99),
+            value!(8.99),
+            value!(22.99),
+            value!(399)
+        ]),
+        query("$.store..price")
+    );
+    assert_eq!(2, query("$.store.*").len());
+    assert_eq!(4, query("$..book[*]").len());
+    assert_eq!(vec![value!("Moby Dick")], query("$..book[2].title"));
+}
+
+#[test]
+fn test_filter() {
+    assert_eq!(
+        sorted(vec![value!("Moby Dick"), value!("The Lord of the Rings")]),
+        query("$..book[?(@.isbn)].title")
+    );
+    assert_eq!(
+        sorted(vec![value!("Sayings of the Century"), value!("Moby Dick")]),
+        query("$.store.book[?(@.price < 10)].title")
+    );
+    assert_eq!(
+        vec![value!("Moby Dick")],
+        query("$.store.book[?(@.price < 10 && @.category == 'fiction')].title")
+    );
+    assert_eq!(
+        vec![value!("Nigel Rees")],
+        query(r#"$.store.book[?(!(@.category == "fiction") || @.price > 100)].author"#)
+    );
+    assert_eq!(
+        vec![value!("The Lord of the Rings")],
+        query("$.store.book[?@.price >= 22.99].title")
+    );
+    assert_eq!(
+        4,
+        query("$.store.book[?(@.price != $.store.bicycle.price)]").len()
+    );
+}
+
+#[test]
+fn test_status_filter() {
+    let v = value!({
+        "items": [
+            {"id": 1, "status": "ok"},
+            {"id": 2, "status": "ng"},
+            {"id": 3, "status": "ok"}
+        ]
+    });
+    let ids = v.query(r#"$.items[?(@.status=="ok")].id"#).unwrap();
+    assert_eq!(vec![&value!(1), &value!(3)], ids);
+}
+
+#[test]
+fn test_invalid() {
+    let cases = vec![
+        "",
+        "store",
+        "$.",
+        "$[",
+        "$[?(@.a ==)]",
+        "$['a]",
+        "$.a b",
+        "$[1 2]",
+    ];
+    for case in cases {
+        assert!(json::JsonPath::from_str(case).is_err(), "{}", case);
+    }
+
+    let deep = format!("$[?({}@.a)]", "!".repeat(100_000));
+    let err = json::JsonPath::from_str(&deep).unwrap_err();
+    assert_eq!(&json::ErrKind::DepthLimitExceeded, err.kind());
+    let deep = format!("$[?({}@.a{})]", "(".repeat(100_000), ")".repeat(100_000));
+    assert!(json::JsonPath::from_str(&deep).is_err());
+    assert!(json::JsonPath::from_str("$[?(!!(@.a))]").is_ok());
+}
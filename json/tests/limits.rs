@@ -0,0 +1,119 @@
+use json::{value, ErrKind, Limits, ParserOptions};
+
+fn parse(s: &str, limits: Limits) -> Result<json::Value, json::Err> {
+    let options = ParserOptions {
+        limits,
+        ..ParserOptions::default()
+    };
+    json::from_str_with_options(s, options)
+}
+
+fn nested(depth: usize) -> String {
+    "[".repeat(depth) + &"]".repeat(depth)
+}
+
+#[test]
+fn test_depth() {
+    assert!(parse(&nested(128), Limits::default()).is_ok());
+    let err = parse(&nested(129), Limits::default()).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+    assert_eq!("nesting too deep at line 1 column 129", err.to_string());
+
+    // hostile input fails before the closing brackets are read.
+    let err = json::from_str_borrowed(&"[".repeat(100_000)).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+    let err = json::from_str_borrowed(&r#"{"a":"#.repeat(100_000)).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+
+    let limits = Limits {
+        max_depth: 10_000,
+        ..Limits::default()
+    };
+    let v = parse(&nested(10_000), limits.clone()).unwrap();
+    assert!(v.pointer(&"/0".repeat(9_999)).is_some());
+    assert_eq!(v, v.clone());
+    assert_eq!(nested(10_000), v.to_string());
+    let err = parse(&nested(10_001), limits.clone()).unwrap_err();
+    assert_eq!(&ErrKind::DepthLimitExceeded, err.kind());
+
+    let limits = Limits {
+        max_depth: 2,
+        ..Limits::default()
+    };
+    assert!(parse(r#"[{"a": 1}, {}, []]"#, limits.clone()).is_ok());
+    assert!(parse(r#"[{"a": {}}]"#, limits.clone()).is_err());
+    assert!(parse(
+        "1",
+        Limits {
+            max_depth: 0,
+            ..limits
+        }
+    )
+    .is_ok());
+}
+
+// values built deeper than any parser limit still drop, clone, compare and print.
+#[test]
+fn test_deep_values() {
+    let mut v = value!(1);
+    let (mut opens, mut closes) = (Vec::new(), String::new());
+    for i in 0..100_000 {
+        if i % 2 == 0 {
+            v = value!([v]);
+            opens.push("[");
+            closes.push(']');
+        } else {
+            v = value!({"a": v});
+            opens.push(r#"{"a":"#);
+            closes.push('}');
+        }
+    }
+    let s: String = opens.into_iter().rev().collect();
+    assert_eq!(s + "1" + &closes, v.to_string());
+    assert_eq!(v, v.clone());
+
+    let mut r = json::ValueRef::Null;
+    for _ in 0..100_000 {
+        r = json::ValueRef::Array(vec![r]);
+    }
+    drop(r);
+}
+
+#[test]
+fn test_input_size() {
+    let limits = Limits {
+        max_input_size: 8,
+        ..Limits::default()
+    };
+    assert_eq!(Ok(value!([1, 2, 3])), parse("[1,2,3] ", limits.clone()));
+    let err = parse("[1, 2, 3]", limits).unwrap_err();
+    assert_eq!(&ErrKind::InputTooLarge, err.kind());
+}
+
+#[test]
+fn test_string_length() {
+    let limits = Limits {
+        max_string_length: 3,
+        ..Limits::default()
+    };
+    assert!(parse(r#"{"abc": "éa"}"#, limits.clone()).is_ok());
+    let cases = vec![r#""abcd""#, r#""ab\n\n""#, r#"{"abcd": 1}"#, r#""éé""#];
+    for case in cases {
+        let err = parse(case, limits.clone()).unwrap_err();
+        assert_eq!(&ErrKind::StringTooLong, err.kind(), "{}", case);
+    }
+}
+
+#[test]
+fn test_object_members() {
+    let limits = Limits {
+        max_object_members: 2,
+        ..Limits::default()
+    };
+    assert!(parse(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#, limits.clone()).is_ok());
+    let cases = vec![r#"{"a": 1, "b": 2, "c": 3}"#, r#"{"a": 1, "a": 2, "a": 3}"#];
+    for case in cases {
+        let err = parse(case, limits.clone()).unwrap_err();
+        assert_eq!(&ErrKind::TooManyMembers, err.kind(), "{}", case);
+    }
+}
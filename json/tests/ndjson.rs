@@ -0,0 +1,90 @@
+use json::{ndjson, value, ErrKind};
+use std::io;
+
+#[test]
+fn test_reader() {
+    let input = "{\"id\": 1}\n\n[1, 2]\r\n  \"three\"  \nnull";
+    let vals: Vec<json::Value> = ndjson::Reader::new(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![
+            value!({"id": 1}),
+            value!([1, 2]),
+            value!("three"),
+            value!(null)
+        ],
+        vals
+    );
+}
+
+#[test]
+fn test_reader_error() {
+    let input = "1\n{\"a\":\n3\n[\n";
+    let results: Vec<Result<json::Value, json::Err>> =
+        ndjson::Reader::new(input.as_bytes()).collect();
+    assert_eq!(4, results.len());
+    assert_eq!(Ok(value!(1)), results[0]);
+    assert_eq!(Some(2), results[1].as_ref().unwrap_err().line());
+    assert_eq!(Ok(value!(3)), results[2]);
+    assert_eq!(Some(4), results[3].as_ref().unwrap_err().line());
+    assert_eq!(
+        "syntax error at line 2 column 6",
+        results[1].as_ref().unwrap_err().to_string()
+    );
+
+    let vals: Vec<json::Value> = ndjson::Reader::new(input.as_bytes())
+        .skip_invalid(true)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(vec![value!(1), value!(3)], vals);
+
+    let vals: Vec<json::Value> = ndjson::Reader::new("{a: 1,}\n[.5, 'b']\n".as_bytes())
+        .options(json::ParserOptions::json5())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(vec![value!({"a": 1}), value!([0.5, "b"])], vals);
+}
+
+struct FailingReader(usize);
+
+impl io::Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0 == 0 {
+            return Err(io::Error::other("broken"));
+        }
+        self.0 = 0;
+        buf[..2].copy_from_slice(b"1\n");
+        Ok(2)
+    }
+}
+
+#[test]
+fn test_reader_io_error() {
+    let results: Vec<Result<json::Value, json::Err>> = ndjson::Reader::new(FailingReader(1))
+        .skip_invalid(true)
+        .collect();
+    assert_eq!(2, results.len());
+    assert_eq!(Ok(value!(1)), results[0]);
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!(&ErrKind::Io("broken".to_string()), err.kind());
+    assert_eq!(Some(2), err.line());
+}
+
+#[test]
+fn test_writer() {
+    let mut w = ndjson::Writer::new(Vec::new());
+    w.write(&value!({"a": "multi\nline"})).unwrap();
+    w.write(&value!([1, 2])).unwrap();
+    w.write(&value!(null)).unwrap();
+    let out = String::from_utf8(w.into_inner()).unwrap();
+    assert_eq!("{\"a\":\"multi\\nline\"}\n[1,2]\nnull\n", out);
+
+    let vals: Vec<json::Value> = ndjson::Reader::new(out.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        vec![value!({"a": "multi\nline"}), value!([1, 2]), value!(null)],
+        vals
+    );
+}
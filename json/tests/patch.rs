@@ -0,0 +1,175 @@
+use json::patch;
+use json::value;
+use std::str::FromStr;
+
+fn parse(s: &str) -> json::Value {
+    json::Value::from_str(s).unwrap()
+}
+
+#[test]
+fn test_apply() {
+    let cases = vec![
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+            r#"{"baz": "qux", "foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": ["bar", "baz"]}"#,
+            r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+        ),
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "remove", "path": "/baz"}]"#,
+            r#"{"foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+            r#"[{"op": "remove", "path": "/foo/1"}]"#,
+            r#"{"foo": ["bar", "baz"]}"#,
+        ),
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+            r#"{"baz": "boo", "foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+            r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+            r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#,
+        ),
+        (
+            r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+            r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+            r#"{"foo": ["all", "cows", "eat", "grass"]}"#,
+        ),
+        (
+            r#"{"foo": {"bar": [1]}}"#,
+            r#"[{"op": "copy", "from": "/foo/bar", "path": "/baz"}]"#,
+            r#"{"foo": {"bar": [1]}, "baz": [1]}"#,
+        ),
+        (
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+            r#"[
+                {"op": "test", "path": "/baz", "value": "qux"},
+                {"op": "test", "path": "/foo/1", "value": 2}
+            ]"#,
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+        ),
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#,
+            r#"{"foo": "bar", "child": {"grandchild": {}}}"#,
+        ),
+        (
+            r#"{"foo": ["bar"]}"#,
+            r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#,
+            r#"{"foo": ["bar", ["abc", "def"]]}"#,
+        ),
+        (
+            r#"{"foo": 1}"#,
+            r#"[{"op": "replace", "path": "", "value": [1]}]"#,
+            r#"[1]"#,
+        ),
+    ];
+    for case in cases {
+        let mut doc = parse(case.0);
+        patch::apply(&mut doc, &parse(case.1)).unwrap();
+        assert_eq!(parse(case.2), doc, "{}", case.1);
+    }
+}
+
+#[test]
+fn test_apply_error() {
+    let cases = vec![
+        r#"[{"op": "add", "path": "/baz/bat", "value": "qux"}]"#,
+        r#"[{"op": "remove", "path": "/none"}]"#,
+        r#"[{"op": "replace", "path": "/none", "value": 1}]"#,
+        r#"[{"op": "test", "path": "/foo", "value": "baz"}]"#,
+        r#"[{"op": "move", "from": "/obj", "path": "/obj/child"}]"#,
+        r#"[{"op": "copy", "from": "/none", "path": "/x"}]"#,
+        r#"[{"op": "add", "path": "/arr/5", "value": 1}]"#,
+        r#"[{"op": "add", "path": "/x"}]"#,
+        r#"[{"op": "unknown", "path": "/foo"}]"#,
+        r#"[{"path": "/foo"}]"#,
+        r#"{"op": "remove", "path": "/foo"}"#,
+    ];
+    for case in cases {
+        let mut doc = value!({"foo": "bar", "obj": {}, "arr": [1]});
+        assert!(patch::apply(&mut doc, &parse(case)).is_err(), "{}", case);
+    }
+
+    // the document is left untouched when an operation fails.
+    let mut doc = value!({"foo": "bar"});
+    let ops = parse(
+        r#"[
+            {"op": "add", "path": "/baz", "value": 1},
+            {"op": "remove", "path": "/none"}
+        ]"#,
+    );
+    assert!(patch::apply(&mut doc, &ops).is_err());
+    assert_eq!(value!({"foo": "bar"}), doc);
+}
+
+#[test]
+fn test_merge() {
+    let cases = vec![
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (
+            r#"{"a":{"b":"c"}}"#,
+            r#"{"a":{"b":"d","c":null}}"#,
+            r#"{"a":{"b":"d"}}"#,
+        ),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (
+            r#"{}"#,
+            r#"{"a":{"bb":{"ccc":null}}}"#,
+            r#"{"a":{"bb":{}}}"#,
+        ),
+    ];
+    for case in cases {
+        let mut doc = parse(case.0);
+        patch::merge(&mut doc, &parse(case.1));
+        assert_eq!(parse(case.2), doc, "{} {}", case.0, case.1);
+    }
+}
+
+#[test]
+fn test_diff() {
+    let cases = vec![
+        (value!({"a": 1}), value!({"a": 1}), 0),
+        (value!({"a": 1, "b": 2}), value!({"a": 1, "c": 2}), 2),
+        (
+            value!({"a": {"b": [1, 2]}}),
+            value!({"a": {"b": [1, 3]}}),
+            1,
+        ),
+        (value!([1, 2, 3]), value!([0, 1, 2, 3]), 1),
+        (value!([1, 2, 3, 4]), value!([1, 4]), 2),
+        (value!([1, 2, 3]), value!([1, 5, 3]), 1),
+        (value!(["a", "b"]), value!(["x", "y", "z"]), 3),
+        (value!({"a/b": 1, "m~n": 2}), value!({"a/b": 2}), 2),
+        (value!([1]), value!({"0": 1}), 1),
+        (value!(null), value!(true), 1),
+    ];
+    for case in cases {
+        let ops = patch::diff(&case.0, &case.1);
+        assert_eq!(case.2, ops.as_array().unwrap().len(), "{}", ops);
+
+        let mut doc = case.0.clone();
+        patch::apply(&mut doc, &ops).unwrap();
+        assert_eq!(case.1, doc);
+    }
+}
@@ -0,0 +1,82 @@
+use json::value;
+
+fn doc() -> json::Value {
+    value!({
+        "foo": ["bar", "baz"],
+        "": 0,
+        "a/b": 1,
+        "m~n": 8,
+        "obj": {"arr": [{"id": 1}, {"id": 2}]}
+    })
+}
+
+#[test]
+fn test_pointer() {
+    let v = doc();
+    let cases = vec![
+        ("", Some(doc())),
+        ("/foo", Some(value!(["bar", "baz"]))),
+        ("/foo/0", Some(value!("bar"))),
+        ("/", Some(value!(0))),
+        ("/a~1b", Some(value!(1))),
+        ("/m~0n", Some(value!(8))),
+        ("/obj/arr/1/id", Some(value!(2))),
+        ("/foo/2", None),
+        ("/foo/01", None),
+        ("/foo/-", None),
+        ("/missing/0", None),
+        ("/foo/0/x", None),
+        ("foo", None),
+        ("/m~2n", None),
+    ];
+    for case in cases {
+        assert_eq!(case.1.as_ref(), v.pointer(case.0), "{}", case.0);
+    }
+}
+
+#[test]
+fn test_pointer_mut() {
+    let mut v = doc();
+    *v.pointer_mut("/obj/arr/0/id").unwrap() = value!(10);
+    assert_eq!(Some(&value!(10)), v.pointer("/obj/arr/0/id"));
+    assert!(v.pointer_mut("/obj/none").is_none());
+}
+
+#[test]
+fn test_index() {
+    let mut v = doc();
+    assert_eq!(value!("baz"), v["foo"][1]);
+    assert_eq!(value!(null), v["foo"][2]);
+    assert_eq!(value!(null), v["none"]["x"][0]);
+
+    v["obj"]["arr"][1]["id"] = value!(20);
+    v["new"]["nested"] = value!(true);
+    assert_eq!(Some(&value!(20)), v.pointer("/obj/arr/1/id"));
+    assert_eq!(Some(&value!(true)), v.pointer("/new/nested"));
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut v = doc();
+    assert_eq!(Ok(None), v.insert("/foo/1", value!("qux")));
+    assert_eq!(Ok(None), v.insert("/foo/-", value!("end")));
+    assert_eq!(
+        Some(&value!(["bar", "qux", "baz", "end"])),
+        v.pointer("/foo")
+    );
+    assert_eq!(Ok(Some(value!(1))), v.insert("/a~1b", value!(2)));
+    assert_eq!(Ok(None), v.insert("/obj/x", value!(3)));
+    assert!(v.insert("/foo/9", value!(0)).is_err());
+    assert!(v.insert("/none/x", value!(0)).is_err());
+
+    assert_eq!(Some(value!("qux")), v.remove("/foo/1"));
+    assert_eq!(Some(value!(2)), v.remove("/a~1b"));
+    assert_eq!(None, v.remove("/a~1b"));
+    assert_eq!(None, v.remove("/foo/9"));
+    assert_eq!(None, v.remove(""));
+    assert_eq!(Some(&value!(["bar", "baz", "end"])), v.pointer("/foo"));
+
+    let old = v.clone();
+    assert_eq!(Ok(Some(old)), v.insert("", value!(null)));
+    assert_eq!(value!(null), v);
+}
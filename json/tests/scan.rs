@@ -0,0 +1,73 @@
+use json::{value, Value};
+use std::str::FromStr;
+
+// lengths around the 16-byte blocks scanned at once.
+const LENS: std::ops::Range<usize> = 0..50;
+
+#[test]
+fn test_strings() {
+    for n in LENS {
+        let plain = "a".repeat(n);
+        let v = Value::from_str(&format!(r#""{}""#, plain)).unwrap();
+        assert_eq!(value!(plain.as_str()), v);
+
+        // an escape and a multi-byte character at every offset.
+        for i in 0..=n {
+            let (head, tail) = plain.split_at(i);
+            let v = Value::from_str(&format!(r#""{}\n{}""#, head, tail)).unwrap();
+            assert_eq!(Value::from(format!("{}\n{}", head, tail)), v);
+
+            let s = format!("{}é{}", head, tail);
+            let v = Value::from_str(&format!(r#""{}""#, s)).unwrap();
+            assert_eq!(Value::from(s), v);
+
+            assert!(Value::from_str(&format!(r#"['{}"{}']"#, head, tail)).is_err());
+            let opts = json::ParserOptions::json5();
+            let v = json::from_str_with_options(&format!(r#"['{}"{}']"#, head, tail), opts);
+            assert_eq!(value!([(format!("{}\"{}", head, tail))]), v.unwrap());
+        }
+
+        // raw control characters and unterminated strings are rejected wherever they are.
+        for i in 0..n {
+            let mut bs = format!(r#""{}""#, plain).into_bytes();
+            bs[i + 1] = b'\t';
+            assert!(json::from_slice(&bs).is_err());
+            assert!(json::from_slice(&bs[..i + 1]).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_whitespace() {
+    for n in LENS {
+        let ws: String = " \n\r\t".chars().cycle().take(n).collect();
+        let s = format!(
+            "{ws}[{ws}1{ws},{ws}{{{ws}\"a\"{ws}:{ws}true{ws}}}{ws}]{ws}",
+            ws = ws
+        );
+        assert_eq!(value!([1, {"a": true}]), Value::from_str(&s).unwrap());
+
+        let s = format!("[{}\x0c1]", ws);
+        assert!(Value::from_str(&s).is_err());
+    }
+}
+
+#[test]
+fn test_numbers() {
+    for n in LENS {
+        let digits: String = "1234567890".chars().cycle().take(n + 1).collect();
+        let cases = vec![
+            digits.clone(),
+            format!("-{}", digits),
+            format!("{}.{}", digits, digits),
+            format!("{}e{}", &digits[..1], n % 300),
+            format!("0.{}E-{}", digits, n),
+        ];
+        for case in cases {
+            let v = Value::from_str(&case).unwrap();
+            assert_eq!(f64::from_str(&case).ok(), v.as_f64(), "{}", case);
+        }
+        assert!(Value::from_str(&format!("{}.", digits)).is_err());
+        assert!(Value::from_str(&format!("{}x", digits)).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use json::schema::Schema;
+use json::value;
+use std::str::FromStr;
+
+fn compile(s: &str) -> Schema {
+    Schema::compile(&json::Value::from_str(s).unwrap()).unwrap()
+}
+
+fn error_paths(schema: &Schema, instance: json::Value) -> Vec<String> {
+    let mut paths: Vec<String> = match schema.validate(&instance) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|e| e.instance_path).collect(),
+    };
+    paths.sort();
+    paths
+}
+
+#[test]
+fn test_user() {
+    let schema = compile(
+        r#"{
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "name": {"type": "string", "minLength": 1, "maxLength": 8},
+                "email": {"type": "string", "pattern": "^[^@]+@[^@]+$"},
+                "role": {"enum": ["admin", "user"]},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true},
+                "a/b": {"type": "null"}
+            },
+            "required": ["id", "name"],
+            "additionalProperties": false
+        }"#,
+    );
+
+    assert!(schema.is_valid(&value!({
+        "id": 1,
+        "name": "alice",
+        "email": "alice@example.com",
+        "role": "admin",
+        "tags": ["a", "b"]
+    })));
+
+    assert_eq!(
+        vec!["/a~1b", "/email", "/id", "/name", "/role", "/tags", "/tags/1", "/unknown"],
+        error_paths(
+            &schema,
+            value!({
+                "id": 0,
+                "name": "too long name",
+                "email": "alice",
+                "role": "guest",
+                "tags": ["a", 1, "a"],
+                "a/b": 1,
+                "unknown": true
+            })
+        )
+    );
+
+    let errors = schema.validate(&value!({"id": 1.5})).unwrap_err();
+    let mut messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    messages.sort();
+    assert_eq!(
+        vec![
+            "/id: expected integer".to_string(),
+            ": missing required property \"name\"".to_string()
+        ],
+        messages
+    );
+}
+
+#[test]
+fn test_type() {
+    let schema = compile(r#"{"type": ["integer", "null"]}"#);
+    assert!(schema.is_valid(&value!(1)));
+    assert!(schema.is_valid(&value!(1.0)));
+    assert!(schema.is_valid(&value!(null)));
+    assert!(!schema.is_valid(&value!(1.5)));
+    assert!(!schema.is_valid(&value!("1")));
+
+    assert!(compile("true").is_valid(&value!({"a": 1})));
+    assert!(!compile("false").is_valid(&value!(null)));
+}
+
+#[test]
+fn test_numbers() {
+    let schema = compile(r#"{"exclusiveMinimum": 0, "maximum": 10, "multipleOf": 2.5}"#);
+    assert!(schema.is_valid(&value!(10)));
+    assert!(schema.is_valid(&value!(2.5)));
+    assert!(!schema.is_valid(&value!(0)));
+    assert!(!schema.is_valid(&value!(12.5)));
+    assert!(!schema.is_valid(&value!(3)));
+    assert!(schema.is_valid(&value!("not a number")));
+
+    let schema = compile(r#"{"multipleOf": 0.1}"#);
+    assert!(schema.is_valid(&value!(0.3)));
+    assert!(schema.is_valid(&value!(0.7)));
+    assert!(schema.is_valid(&value!(12)));
+    assert!(!schema.is_valid(&value!(0.35)));
+    assert!(!compile(r#"{"multipleOf": 0.01}"#).is_valid(&value!(0.015)));
+}
+
+#[test]
+fn test_arrays() {
+    let schema = compile(
+        r#"{
+            "prefixItems": [{"type": "string"}, {"type": "number"}],
+            "items": {"type": "boolean"},
+            "minItems": 2,
+            "maxItems": 3
+        }"#,
+    );
+    assert!(schema.is_valid(&value!(["a", 1])));
+    assert!(schema.is_valid(&value!(["a", 1, true])));
+    assert_eq!(vec!["/0", "/2"], error_paths(&schema, value!([1, 1, 1])));
+    assert_eq!(vec![""], error_paths(&schema, value!(["a"])));
+    assert_eq!(
+        vec![""],
+        error_paths(&schema, value!(["a", 1, true, false]))
+    );
+}
+
+#[test]
+fn test_combinators() {
+    let schema = compile(
+        r#"{
+            "anyOf": [{"type": "string"}, {"type": "number"}],
+            "oneOf": [{"minimum": 0}, {"maximum": 10}],
+            "not": {"const": 100}
+        }"#,
+    );
+    assert!(schema.is_valid(&value!(-1)));
+    assert!(schema.is_valid(&value!(11)));
+    assert!(!schema.is_valid(&value!(5)));
+    assert!(!schema.is_valid(&value!(100)));
+    assert!(!schema.is_valid(&value!(null)));
+}
+
+#[test]
+fn test_ref() {
+    let schema = compile(
+        r##"{
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": {"type": "integer"},
+                        "children": {"type": "array", "items": {"$ref": "#/$defs/node"}}
+                    },
+                    "required": ["value"]
+                }
+            },
+            "$ref": "#/$defs/node"
+        }"##,
+    );
+    assert!(schema.is_valid(&value!({
+        "value": 1,
+        "children": [{"value": 2}, {"value": 3, "children": [{"value": 4}]}]
+    })));
+    assert_eq!(
+        vec!["/children/1/children/0/value"],
+        error_paths(
+            &schema,
+            value!({
+                "value": 1,
+                "children": [{"value": 2}, {"value": 3, "children": [{"value": "x"}]}]
+            })
+        )
+    );
+}
+
+#[test]
+fn test_invalid_schema() {
+    let cases = vec![
+        r#"1"#,
+        r#"{"type": 1}"#,
+        r##"{"$ref": "#/none"}"##,
+        r#"{"$ref": "other.json"}"#,
+        r#"{"pattern": "("}"#,
+        r#"{"minLength": -1}"#,
+        r#"{"required": "id"}"#,
+        // reference cycles which never descend into the instance.
+        r##"{"$ref": "#"}"##,
+        r##"{"$defs": {"a": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"}"##,
+        r##"{"$defs": {"a": {"allOf": [{"not": {"$ref": "#"}}]}}, "anyOf": [{"$ref": "#/$defs/a"}]}"##,
+    ];
+    for case in cases {
+        assert!(
+            Schema::compile(&json::Value::from_str(case).unwrap()).is_err(),
+            "{}",
+            case
+        );
+    }
+}
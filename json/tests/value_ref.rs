@@ -0,0 +1,52 @@
+use json::ValueRef;
+use std::borrow::Cow;
+use std::str::FromStr;
+
+#[test]
+fn test_borrowed_strings() {
+    let v = json::from_str_borrowed(r#"{"key": "plain", "esc\"aped": "a\nb"}"#).unwrap();
+    let obj = v.as_object().unwrap();
+    assert!(matches!(obj[0].0, Cow::Borrowed("key")));
+    assert!(matches!(obj[0].1, ValueRef::String(Cow::Borrowed("plain"))));
+    assert!(matches!(&obj[1].0, Cow::Owned(s) if s == "esc\"aped"));
+    assert!(matches!(&obj[1].1, ValueRef::String(Cow::Owned(s)) if s == "a\nb"));
+
+    let v = json::from_str_borrowed(r#"["日本語", "été", ""]"#).unwrap();
+    let arr = v.as_array().unwrap();
+    assert!(matches!(arr[0], ValueRef::String(Cow::Borrowed("日本語"))));
+    assert_eq!(Some("été"), arr[1].as_str());
+    assert!(matches!(arr[2], ValueRef::String(Cow::Borrowed(""))));
+}
+
+#[test]
+fn test_same_as_owned() {
+    let cases = vec![
+        r#"null"#,
+        r#"[true, false, 1, -2, 3.5e3]"#,
+        r#"{"a": {"b": ["c\td", {"e": null}]}, "f": "😀"}"#,
+        r#"{"a": 1, "a": 2}"#,
+    ];
+    for case in cases {
+        let borrowed = json::from_str_borrowed(case).unwrap();
+        assert_eq!(
+            json::Value::from_str(case).unwrap(),
+            json::Value::from(borrowed)
+        );
+    }
+}
+
+#[test]
+fn test_get() {
+    let v = json::from_str_borrowed(r#"{"a": 1, "b": "x", "a": 2}"#).unwrap();
+    assert_eq!(Some(2), v.get("a").and_then(|v| v.as_i64()));
+    assert_eq!(Some("x"), v.get("b").and_then(|v| v.as_str()));
+    assert!(v.get("c").is_none());
+}
+
+#[test]
+fn test_invalid() {
+    let cases: Vec<&[u8]> = vec![b"\"\xff\"", b"[\"a\"", b"{\"a\\x\": 1}", b"\"\x01\""];
+    for case in cases {
+        assert!(json::from_slice_borrowed(case).is_err());
+    }
+}
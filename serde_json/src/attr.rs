@@ -0,0 +1,418 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Generics, Ident, Lit, LitStr, Meta, NestedMeta,
+    Path, Type,
+};
+
+// `#[json(...)]` on a struct or enum.
+#[derive(Clone, Default)]
+pub struct Container {
+    pub rename_all: Option<RenameRule>,
+    pub deny_unknown_fields: bool,
+    pub tagging: Tagging,
+}
+
+// how the variant of an enum is represented.
+#[derive(Clone, Default)]
+pub enum Tagging {
+    // `{"Variant": content}`, or `"Variant"` for a unit variant.
+    #[default]
+    External,
+    // `{"tag": "Variant", ...fields}`. a newtype variant whose content turns out not to be
+    // an object, through an alias the derive can't see, puts it under `INTERNAL_CONTENT`.
+    Internal {
+        tag: String,
+    },
+    // `{"tag": "Variant", "content": content}`.
+    Adjacent {
+        tag: String,
+        content: String,
+    },
+    // just the content, the first variant which reads it wins.
+    Untagged,
+}
+
+pub const INTERNAL_CONTENT: &str = "value";
+
+// `#[json(...)]` on a named field.
+#[derive(Default)]
+pub struct Field {
+    pub rename: Option<String>,
+    // the expression which builds a missing or skipped field.
+    pub default: Option<TokenStream>,
+    pub skip: bool,
+    pub skip_serializing_if: Option<Path>,
+    pub flatten: bool,
+}
+
+// `#[json(...)]` on an enum variant.
+#[derive(Default)]
+pub struct Variant {
+    pub rename: Option<String>,
+}
+
+impl Container {
+    pub fn from_ast(ast: &DeriveInput) -> Result<Self, Error> {
+        let mut c = Container::default();
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = false;
+        for meta in metas(&ast.attrs)? {
+            match name(&meta).as_str() {
+                "rename_all" => {
+                    let lit = string(&meta)?;
+                    let rule = RenameRule::from_str(&lit.value()).ok_or_else(|| {
+                        Error::new_spanned(
+                            &lit,
+                            "unknown case, expected one of lowercase, UPPERCASE, PascalCase, \
+                             camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case or \
+                             SCREAMING-KEBAB-CASE",
+                        )
+                    })?;
+                    set(&mut c.rename_all, rule, &meta)?;
+                }
+                "deny_unknown_fields" => set_flag(&mut c.deny_unknown_fields, &meta)?,
+                "tag" | "content" | "untagged" if !matches!(ast.data, Data::Enum(_)) => {
+                    return Err(Error::new_spanned(
+                        meta.path(),
+                        format!("`{}` is only supported on enums", name(&meta)),
+                    ))
+                }
+                "tag" => set(&mut tag, (string(&meta)?.value(), meta.clone()), &meta)?,
+                "content" => set(&mut content, (string(&meta)?.value(), meta.clone()), &meta)?,
+                "untagged" => set_flag(&mut untagged, &meta)?,
+                _ => return Err(unknown(&meta)),
+            }
+        }
+        c.tagging = match (tag, content) {
+            (Some((_, meta)), _) if untagged => {
+                return Err(Error::new_spanned(meta, "an untagged enum has no tag"))
+            }
+            (None, Some((_, meta))) => {
+                return Err(Error::new_spanned(meta, "`content` requires a `tag`"))
+            }
+            (Some((tag, _)), Some((content, _))) => Tagging::Adjacent { tag, content },
+            (Some((tag, _)), None) => Tagging::Internal { tag },
+            (None, None) if untagged => Tagging::Untagged,
+            (None, None) => Tagging::External,
+        };
+        if let (Tagging::Internal { .. }, Data::Enum(e)) = (&c.tagging, &ast.data) {
+            // the fields of a tuple variant have no keys to put next to the tag.
+            let tuple = e.variants.iter().find(|v| match &v.fields {
+                Fields::Unnamed(fields) => fields.unnamed.len() > 1,
+                _ => false,
+            });
+            if let Some(v) = tuple {
+                return Err(Error::new_spanned(
+                    v,
+                    "an internally tagged enum cannot have tuple variants",
+                ));
+            }
+            // nor does a newtype variant holding anything but a struct, which only a plain
+            // path can name.
+            let newtype = e.variants.iter().find_map(|v| match &v.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    Some(&fields.unnamed[0].ty).filter(|ty| !maybe_struct(ty, &ast.generics))
+                }
+                _ => None,
+            });
+            if let Some(ty) = newtype {
+                return Err(Error::new_spanned(
+                    ty,
+                    "an internally tagged enum can only have newtype variants of struct types",
+                ));
+            }
+        }
+        Ok(c)
+    }
+
+    // the key of an internal tag, which is not an unknown field.
+    pub fn internal_tag(&self) -> Option<&str> {
+        match &self.tagging {
+            Tagging::Internal { tag } => Some(tag),
+            _ => None,
+        }
+    }
+}
+
+impl Field {
+    pub fn from_ast(f: &syn::Field, c: &Container) -> Result<Self, Error> {
+        let mut field = Field::default();
+        let metas = metas(&f.attrs)?;
+        if let (None, Some(meta)) = (&f.ident, metas.first()) {
+            return Err(Error::new_spanned(
+                meta,
+                "attributes are only supported on named fields",
+            ));
+        }
+        for meta in metas {
+            match name(&meta).as_str() {
+                "rename" => set(&mut field.rename, string(&meta)?.value(), &meta)?,
+                "default" => {
+                    let default = match &meta {
+                        Meta::Path(_) => quote!(::std::default::Default::default()),
+                        _ => {
+                            let path = string(&meta)?.parse::<Path>()?;
+                            quote!(#path())
+                        }
+                    };
+                    set(&mut field.default, default, &meta)?;
+                }
+                "skip" => set_flag(&mut field.skip, &meta)?,
+                "skip_serializing_if" => {
+                    let path = string(&meta)?.parse()?;
+                    set(&mut field.skip_serializing_if, path, &meta)?;
+                }
+                "flatten" => {
+                    if c.deny_unknown_fields {
+                        return Err(Error::new_spanned(
+                            meta,
+                            "flatten cannot be used with deny_unknown_fields",
+                        ));
+                    }
+                    set_flag(&mut field.flatten, &meta)?
+                }
+                _ => return Err(unknown(&meta)),
+            }
+        }
+        if field.flatten && field.rename.is_some() {
+            return Err(Error::new_spanned(
+                f,
+                "a flattened field has no key to rename",
+            ));
+        }
+        if field.rename.is_none() {
+            if let Some(ident) = &f.ident {
+                field.rename = Some(key(ident, c.rename_all));
+            }
+        }
+        Ok(field)
+    }
+
+    // the key of the field in the JSON object.
+    pub fn key(&self) -> &str {
+        self.rename.as_deref().unwrap_or("")
+    }
+}
+
+impl Variant {
+    pub fn from_ast(v: &syn::Variant, c: &Container) -> Result<Self, Error> {
+        let mut variant = Variant::default();
+        for meta in metas(&v.attrs)? {
+            match name(&meta).as_str() {
+                "rename" => set(&mut variant.rename, string(&meta)?.value(), &meta)?,
+                _ => return Err(unknown(&meta)),
+            }
+        }
+        if variant.rename.is_none() {
+            variant.rename = Some(key(&v.ident, c.rename_all));
+        }
+        Ok(variant)
+    }
+
+    // the name of the variant in JSON.
+    pub fn tag(&self) -> &str {
+        self.rename.as_deref().unwrap_or("")
+    }
+}
+
+// whether a type may be a struct: a path without generic arguments which names neither a
+// type parameter nor one of the types of the json crate that aren't objects. an alias can
+// still hide any type.
+fn maybe_struct(ty: &Type, generics: &Generics) -> bool {
+    let path = match ty {
+        Type::Paren(p) => return maybe_struct(&p.elem, generics),
+        Type::Group(g) => return maybe_struct(&g.elem, generics),
+        Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => return false,
+    };
+    if path.segments.iter().any(|s| !s.arguments.is_empty()) {
+        return false;
+    }
+    if let Some(ident) = path.get_ident() {
+        if generics.type_params().any(|p| p.ident == *ident) {
+            return false;
+        }
+    }
+    match path.segments.last() {
+        Some(segment) => !matches!(
+            segment.ident.to_string().as_str(),
+            "bool"
+                | "char"
+                | "str"
+                | "String"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "f32"
+                | "f64"
+                | "Number"
+                | "Value"
+        ),
+        None => false,
+    }
+}
+
+fn key(ident: &Ident, rule: Option<RenameRule>) -> String {
+    let name = ident.unraw().to_string();
+    match rule {
+        Some(rule) => rule.apply(&name),
+        None => name,
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    // renames a snake_case field or a PascalCase variant.
+    pub fn apply(self, name: &str) -> String {
+        let words = words(name);
+        match self {
+            RenameRule::Lower => name.to_ascii_lowercase(),
+            RenameRule::Upper => name.to_ascii_uppercase(),
+            RenameRule::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            RenameRule::Camel => {
+                let mut s = words.first().cloned().unwrap_or_default();
+                s.extend(words.iter().skip(1).map(|w| capitalize(w)));
+                s
+            }
+            RenameRule::Snake => words.join("_"),
+            RenameRule::ScreamingSnake => words.join("_").to_ascii_uppercase(),
+            RenameRule::Kebab => words.join("-"),
+            RenameRule::ScreamingKebab => words.join("-").to_ascii_uppercase(),
+        }
+    }
+}
+
+// splits a name into lowercase words at underscores and lower-to-upper case changes.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        let boundary = c == '_' || (c.is_ascii_uppercase() && prev_lower);
+        if boundary && !word.is_empty() {
+            words.push(word);
+            word = String::new();
+        }
+        if c != '_' {
+            word.push(c.to_ascii_lowercase());
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+// the items of all the `#[json(...)]` attributes.
+fn metas(attrs: &[Attribute]) -> Result<Vec<Meta>, Error> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("json")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => metas.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            return Err(Error::new_spanned(lit, "expected an attribute name"))
+                        }
+                    }
+                }
+            }
+            meta => return Err(Error::new_spanned(meta, "expected #[json(...)]")),
+        }
+    }
+    Ok(metas)
+}
+
+fn name(meta: &Meta) -> String {
+    match meta.path().get_ident() {
+        Some(ident) => ident.to_string(),
+        None => String::new(),
+    }
+}
+
+fn unknown(meta: &Meta) -> Error {
+    let path = meta.path();
+    Error::new_spanned(path, format!("unknown json attribute `{}`", quote!(#path)))
+}
+
+fn string(meta: &Meta) -> Result<LitStr, Error> {
+    match meta {
+        Meta::NameValue(nv) => match &nv.lit {
+            Lit::Str(lit) => Ok(lit.clone()),
+            lit => Err(Error::new_spanned(lit, "expected a string")),
+        },
+        _ => Err(Error::new_spanned(
+            meta,
+            format!("expected `{} = \"...\"`", name(meta)),
+        )),
+    }
+}
+
+fn set<T>(slot: &mut Option<T>, value: T, meta: &Meta) -> Result<(), Error> {
+    if slot.is_some() {
+        return Err(Error::new_spanned(meta.path(), "duplicate json attribute"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn set_flag(flag: &mut bool, meta: &Meta) -> Result<(), Error> {
+    match meta {
+        Meta::Path(_) if !*flag => {
+            *flag = true;
+            Ok(())
+        }
+        Meta::Path(path) => Err(Error::new_spanned(path, "duplicate json attribute")),
+        _ => Err(Error::new_spanned(
+            meta,
+            format!("expected `{}`", name(meta)),
+        )),
+    }
+}
@@ -0,0 +1,24 @@
+use proc_macro2::TokenStream;
+use syn::{parse_quote, Generics, Lifetime, LifetimeDef};
+
+// adds `T: bound` to the where clause for every type parameter `T`.
+pub fn with_bound(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+// adds a first lifetime `de` which outlives every lifetime of `generics`, so
+// borrowed fields can point into the converted value.
+pub fn with_lifetime(generics: &Generics, de: &Lifetime) -> Generics {
+    let mut generics = generics.clone();
+    let mut def = LifetimeDef::new(de.clone());
+    def.bounds = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    def.colon_token = Some(Default::default());
+    generics.params.insert(0, def.into());
+    generics
+}
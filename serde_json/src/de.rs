@@ -0,0 +1,291 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DataEnum, DeriveInput, Error, Fields, Ident, Lifetime};
+
+use crate::attr::{self, Container, Tagging, INTERNAL_CONTENT};
+use crate::bound;
+
+pub fn expand(ast: &DeriveInput) -> Result<TokenStream, Error> {
+    let name = &ast.ident;
+    let c = Container::from_ast(ast)?;
+    let body = match &ast.data {
+        Data::Struct(s) => from_fields(quote!(#name), &s.fields, &c)?,
+        Data::Enum(e) => from_variants(name, e, &c)?,
+        Data::Union(u) => {
+            return Err(Error::new_spanned(
+                u.union_token,
+                "unions are not supported",
+            ))
+        }
+    };
+
+    let de = Lifetime::new("'__de", Span::call_site());
+    let generics = bound::with_bound(&ast.generics, quote!(::json::FromJson<#de>));
+    let generics = bound::with_lifetime(&generics, &de);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::json::FromJson<#de> for #name #ty_generics #where_clause {
+            fn from_json(v: &#de ::json::Value) -> ::std::result::Result<Self, ::json::Err> {
+                #body
+            }
+        }
+    })
+}
+
+// builds `path`, a struct or a variant, from `v`.
+// a struct with named fields is an object, a newtype is its field, a tuple struct is
+// an array and a unit struct is null.
+fn from_fields(path: TokenStream, fields: &Fields, c: &Container) -> Result<TokenStream, Error> {
+    let attrs = fields
+        .iter()
+        .map(|f| attr::Field::from_ast(f, c))
+        .collect::<Result<Vec<_>, _>>()?;
+    // a field type without an impl is reported at the field.
+    let from_json: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let ty = &f.ty;
+            quote_spanned!(ty.span()=> <#ty as ::json::FromJson>::from_json)
+        })
+        .collect();
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let inits = fields.named.iter().zip(&attrs).zip(&from_json);
+            let inits = inits.map(|((f, attr), from_json)| named_field(f, attr, from_json));
+            let deny = if c.deny_unknown_fields {
+                let keys = attrs.iter().filter(|a| !a.skip).map(|a| a.key());
+                let keys = keys.chain(c.internal_tag());
+                quote! {
+                    for key in __obj.keys() {
+                        if ![#(#keys),*].contains(&key.as_str()) {
+                            return ::std::result::Result::Err(::json::Err::unknown_field(key));
+                        }
+                    }
+                }
+            } else {
+                quote!()
+            };
+            quote! {
+                let __obj = match v {
+                    ::json::Value::Object(obj) => obj,
+                    v => {
+                        return ::std::result::Result::Err(
+                            ::json::Err::invalid_type("object", v.type_name()),
+                        )
+                    }
+                };
+                #deny
+                ::std::result::Result::Ok(#path { #(#inits,)* })
+            }
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let from_json = &from_json[0];
+            quote!(#from_json(v).map(#path))
+        }
+        Fields::Unnamed(fields) => {
+            let len = fields.unnamed.len();
+            let expected = format!("array of length {}", len);
+            let elems = from_json.iter().enumerate().map(|(i, from_json)| {
+                let index = i.to_string();
+                quote!(#from_json(&arr[#i]).map_err(|e| e.at(#index))?)
+            });
+            quote! {
+                match v {
+                    ::json::Value::Array(arr) if arr.len() == #len => {
+                        ::std::result::Result::Ok(#path(#(#elems,)*))
+                    }
+                    v => ::std::result::Result::Err(
+                        ::json::Err::invalid_type(#expected, v.type_name()),
+                    ),
+                }
+            }
+        }
+        Fields::Unit => quote! {
+            match v {
+                ::json::Value::Null => ::std::result::Result::Ok(#path),
+                v => ::std::result::Result::Err(::json::Err::invalid_type("null", v.type_name())),
+            }
+        },
+    })
+}
+
+// an enum is tagged by the name of its variant as set by `c.tagging`.
+fn from_variants(name: &Ident, e: &DataEnum, c: &Container) -> Result<TokenStream, Error> {
+    // `rename_all` names the variants, not their fields.
+    let fields = Container {
+        rename_all: None,
+        ..c.clone()
+    };
+    let mut unit_tags = Vec::new();
+    let mut unit_idents = Vec::new();
+    let mut tags = Vec::new();
+    let mut bodies = Vec::new();
+    for variant in &e.variants {
+        let ident = &variant.ident;
+        let tag = attr::Variant::from_ast(variant, c)?.tag().to_string();
+        if let Fields::Unit = variant.fields {
+            unit_tags.push(tag.clone());
+            unit_idents.push(ident);
+        } else {
+            tags.push(tag);
+            let body = from_fields(quote!(#name::#ident), &variant.fields, &fields)?;
+            bodies.push(match (&c.tagging, &variant.fields) {
+                // the content of a newtype which isn't a struct is under a key of its own.
+                (Tagging::Internal { .. }, Fields::Unnamed(_)) => quote! {
+                    let variant = |v: &'__de ::json::Value| -> ::std::result::Result<Self, ::json::Err> {
+                        #body
+                    };
+                    match (variant(v), __obj.get(#INTERNAL_CONTENT)) {
+                        (::std::result::Result::Err(_), ::std::option::Option::Some(v))
+                            if __obj.len() == 2 =>
+                        {
+                            variant(v).map_err(|e| e.at(#INTERNAL_CONTENT))
+                        }
+                        (result, _) => result,
+                    }
+                },
+                _ => body,
+            });
+        }
+    }
+    let units = quote! {
+        #(#unit_tags => ::std::result::Result::Ok(#name::#unit_idents),)*
+    };
+    let unknown = quote! {
+        tag => ::std::result::Result::Err(::json::Err::unknown_variant(tag)),
+    };
+    Ok(match &c.tagging {
+        Tagging::External => quote! {
+            match v {
+                ::json::Value::String(tag) => match tag.as_str() {
+                    #units
+                    #unknown
+                },
+                ::json::Value::Object(obj) if obj.len() == 1 => {
+                    let (tag, v) = obj.iter().next().unwrap();
+                    let variant = || -> ::std::result::Result<Self, ::json::Err> {
+                        match tag.as_str() {
+                            #units
+                            #(#tags => { #bodies })*
+                            #unknown
+                        }
+                    };
+                    variant().map_err(|e| e.at(tag))
+                }
+                v => ::std::result::Result::Err(::json::Err::invalid_type(
+                    "string or object with a single member",
+                    v.type_name(),
+                )),
+            }
+        },
+        // the fields of the variant are read from the object holding the tag.
+        Tagging::Internal { tag } => {
+            let read_tag = read_tag(tag);
+            quote! {
+                #read_tag
+                match tag.as_str() {
+                    #units
+                    #(#tags => { #bodies })*
+                    #unknown
+                }
+            }
+        }
+        Tagging::Adjacent { tag, content } => {
+            let read_tag = read_tag(tag);
+            quote! {
+                #read_tag
+                match tag.as_str() {
+                    #units
+                    #(#tags => match __obj.get(#content) {
+                        ::std::option::Option::Some(v) => {
+                            let variant = || -> ::std::result::Result<Self, ::json::Err> {
+                                #bodies
+                            };
+                            variant().map_err(|e| e.at(#content))
+                        }
+                        ::std::option::Option::None => {
+                            ::std::result::Result::Err(::json::Err::missing_field(#content))
+                        }
+                    },)*
+                    #unknown
+                }
+            }
+        }
+        Tagging::Untagged => {
+            // unit variants are all null, so only the first can be read.
+            let unit_idents = unit_idents.iter().take(1);
+            let enum_name = name.to_string();
+            quote! {
+                #(
+                    if let ::json::Value::Null = v {
+                        return ::std::result::Result::Ok(#name::#unit_idents);
+                    }
+                )*
+                #(
+                    let variant = || -> ::std::result::Result<Self, ::json::Err> { #bodies };
+                    if let ::std::result::Result::Ok(v) = variant() {
+                        return ::std::result::Result::Ok(v);
+                    }
+                )*
+                ::std::result::Result::Err(::json::Err::no_matching_variant(#enum_name))
+            }
+        }
+    })
+}
+
+// binds `__obj` to the object `v` and `tag` to the string at its `key`.
+fn read_tag(key: &str) -> TokenStream {
+    quote! {
+        let __obj = match v {
+            ::json::Value::Object(obj) => obj,
+            v => {
+                return ::std::result::Result::Err(
+                    ::json::Err::invalid_type("object", v.type_name()),
+                )
+            }
+        };
+        let tag = match __obj.get(#key) {
+            ::std::option::Option::Some(::json::Value::String(tag)) => tag,
+            ::std::option::Option::Some(v) => {
+                return ::std::result::Result::Err(
+                    ::json::Err::invalid_type("string", v.type_name()).at(#key),
+                )
+            }
+            ::std::option::Option::None => {
+                return ::std::result::Result::Err(::json::Err::missing_field(#key))
+            }
+        };
+    }
+}
+
+// the initializer of a named field, read from `__obj`.
+fn named_field(f: &syn::Field, attr: &attr::Field, from_json: &TokenStream) -> TokenStream {
+    let ident = &f.ident;
+    let ty = &f.ty;
+    let key = attr.key();
+    let default = attr
+        .default
+        .clone()
+        .unwrap_or_else(|| quote!(::std::default::Default::default()));
+    let missing = match &attr.default {
+        Some(default) => default.clone(),
+        None => {
+            let from_missing = quote_spanned!(ty.span()=> <#ty as ::json::FromJson>::from_missing);
+            quote!(#from_missing().ok_or_else(|| ::json::Err::missing_field(#key))?)
+        }
+    };
+    if attr.skip {
+        quote!(#ident: #default)
+    } else if attr.flatten {
+        quote!(#ident: #from_json(v)?)
+    } else {
+        quote! {
+            #ident: match __obj.get(#key) {
+                ::std::option::Option::Some(v) => #from_json(v).map_err(|e| e.at(#key))?,
+                ::std::option::Option::None => #missing,
+            }
+        }
+    }
+}
@@ -1,45 +1,27 @@
 extern crate proc_macro;
 
+mod attr;
+mod bound;
+mod de;
+mod ser;
+
 use proc_macro::TokenStream;
-use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Deserialize)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse_macro_input!(input as DeriveInput);
-
-    let name = &ast.ident;
-    let fields: Vec<(&syn::Ident, &syn::Ident)> = match ast.data {
-        syn::Data::Struct(ref s) => s
-            .fields
-            .iter()
-            .filter_map(|field| match (&field.ident, type_ident(field)) {
-                (Some(name), Some(ty)) => Some((name, ty)),
-                _ => None,
-            })
-            .collect(),
-        syn::Data::Enum(_) => unreachable!(),
-        syn::Data::Union(_) => unreachable!(),
-    };
-
-    println!("fields: {:?}", fields);
-
-    let tokens = quote! {
-        impl #name {
-            fn foo(&self) {
-                println!("foo");
-            }
-        }
-    };
-    TokenStream::from(tokens)
+// implements `json::FromJson`, so the deriving crate has to depend on `json`.
+#[proc_macro_derive(Deserialize, attributes(json))]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    de::expand(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-fn type_ident(field: &syn::Field) -> Option<&syn::Ident> {
-    match &field.ty {
-        syn::Type::Path(p) => match p.path.segments.first() {
-            Some(seg) => Some(&seg.ident),
-            _ => None,
-        },
-        _ => None, // TODO
-    }
+// implements `json::ToJson`.
+#[proc_macro_derive(Serialize, attributes(json))]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    ser::expand(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
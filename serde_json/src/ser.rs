@@ -0,0 +1,184 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Ident};
+
+use crate::attr::{self, Container, Tagging, INTERNAL_CONTENT};
+use crate::bound;
+
+pub fn expand(ast: &DeriveInput) -> Result<TokenStream, Error> {
+    let name = &ast.ident;
+    let c = Container::from_ast(ast)?;
+    let body = match &ast.data {
+        Data::Struct(s) => {
+            let values = s.fields.iter().enumerate().map(|(i, f)| match &f.ident {
+                Some(ident) => quote!(&self.#ident),
+                None => {
+                    let index = syn::Index::from(i);
+                    quote!(&self.#index)
+                }
+            });
+            to_fields(&s.fields, values.collect(), &c)?
+        }
+        Data::Enum(e) if e.variants.is_empty() => quote!(match *self {}),
+        Data::Enum(e) => {
+            // `rename_all` names the variants, not their fields.
+            let fields_attrs = Container {
+                rename_all: None,
+                ..c.clone()
+            };
+            let mut arms = Vec::new();
+            for variant in &e.variants {
+                let ident = &variant.ident;
+                let bindings = bindings(&variant.fields);
+                let pat = match &variant.fields {
+                    Fields::Named(_) => quote!(#name::#ident { #(#bindings),* }),
+                    Fields::Unnamed(_) => quote!(#name::#ident(#(#bindings),*)),
+                    Fields::Unit => quote!(#name::#ident),
+                };
+                let tag_name = attr::Variant::from_ast(variant, &c)?.tag().to_string();
+                let tag = quote!(::json::Value::String(::std::string::String::from(#tag_name)));
+                let values = bindings.iter().map(|b| quote!(#b)).collect();
+                let inner = to_fields(&variant.fields, values, &fields_attrs)?;
+                let unit = matches!(variant.fields, Fields::Unit);
+                let value = match &c.tagging {
+                    Tagging::External if unit => tag,
+                    Tagging::External => object(vec![(quote!(#tag_name), inner)]),
+                    Tagging::Internal { tag: key } if unit => object(vec![(quote!(#key), tag)]),
+                    // the fields of a struct go next to the tag. `Container::from_ast` allows
+                    // only structs in newtype variants, but an alias may still hold another
+                    // type, whose value goes under a key of its own.
+                    Tagging::Internal { tag: key } => {
+                        let fallback = object(vec![
+                            (quote!(#key), tag.clone()),
+                            (quote!(#INTERNAL_CONTENT), quote!(v)),
+                        ]);
+                        quote! {{
+                            let mut v = #inner;
+                            match &mut v {
+                                ::json::Value::Object(obj) => {
+                                    obj.insert(::std::string::String::from(#key), #tag);
+                                    v
+                                }
+                                _ => #fallback,
+                            }
+                        }}
+                    }
+                    Tagging::Adjacent { tag: key, .. } if unit => object(vec![(quote!(#key), tag)]),
+                    Tagging::Adjacent { tag: key, content } => {
+                        object(vec![(quote!(#key), tag), (quote!(#content), inner)])
+                    }
+                    Tagging::Untagged => inner,
+                };
+                arms.push(quote!(#pat => #value));
+            }
+            quote! {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(Error::new_spanned(
+                u.union_token,
+                "unions are not supported",
+            ))
+        }
+    };
+
+    let generics = bound::with_bound(&ast.generics, quote!(::json::ToJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::json::ToJson for #name #ty_generics #where_clause {
+            fn to_json(&self) -> ::json::Value {
+                #body
+            }
+        }
+    })
+}
+
+// the names the fields of a variant are bound to in a match arm.
+fn bindings(fields: &Fields) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("__{}", i),
+        })
+        .collect()
+}
+
+// converts `values`, references to the fields, the same way `de::from_fields` reads them.
+fn to_fields(
+    fields: &Fields,
+    values: Vec<TokenStream>,
+    c: &Container,
+) -> Result<TokenStream, Error> {
+    let attrs = fields
+        .iter()
+        .map(|f| attr::Field::from_ast(f, c))
+        .collect::<Result<Vec<_>, _>>()?;
+    // a field type without an impl is reported at the field.
+    let jsons: Vec<_> = fields
+        .iter()
+        .zip(&values)
+        .map(|(f, value)| {
+            let ty = &f.ty;
+            quote_spanned!(ty.span()=> <#ty as ::json::ToJson>::to_json(#value))
+        })
+        .collect();
+    Ok(match fields {
+        Fields::Named(_) => {
+            let members = attrs.iter().zip(values).zip(jsons);
+            let inserts =
+                members
+                    .filter(|((attr, _), _)| !attr.skip)
+                    .map(|((attr, value), json)| {
+                        let key = attr.key();
+                        let insert = if attr.flatten {
+                            // flattened values other than objects, such as a None, add no members.
+                            quote! {
+                                if let ::json::Value::Object(inner) = &mut #json {
+                                    obj.extend(inner.drain());
+                                }
+                            }
+                        } else {
+                            quote! {
+                                obj.insert(::std::string::String::from(#key), #json);
+                            }
+                        };
+                        match &attr.skip_serializing_if {
+                            Some(path) => quote! {
+                                if !#path(#value) {
+                                    #insert
+                                }
+                            },
+                            None => insert,
+                        }
+                    });
+            quote! {{
+                #[allow(unused_mut)]
+                let mut obj = ::std::collections::HashMap::new();
+                #(#inserts)*
+                ::json::Value::Object(::std::boxed::Box::new(obj))
+            }}
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => jsons[0].clone(),
+        Fields::Unnamed(_) => quote! {
+            ::json::Value::Array(::std::vec![#(#jsons),*])
+        },
+        Fields::Unit => quote!(::json::Value::Null),
+    })
+}
+
+fn object(members: Vec<(TokenStream, TokenStream)>) -> TokenStream {
+    let members = members
+        .into_iter()
+        .map(|(key, value)| quote!((::std::string::String::from(#key), #value)));
+    quote! {
+        ::json::Value::Object(::std::boxed::Box::new(
+            ::std::iter::IntoIterator::into_iter([#(#members),*]).collect(),
+        ))
+    }
+}
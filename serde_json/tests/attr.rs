@@ -0,0 +1,141 @@
+use json::{value, FromJson, FromJsonOwned, ToJson, Value};
+use serde_json::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(rename_all = "camelCase")]
+struct User {
+    user_id: u64,
+    #[json(rename = "name")]
+    display_name: String,
+    #[json(default)]
+    tags: Vec<String>,
+    #[json(default = "default_level")]
+    level: u8,
+    #[json(skip)]
+    cache: Option<String>,
+    #[json(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[json(flatten)]
+    extra: Extra,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Extra {
+    #[json(default)]
+    created_at: Option<String>,
+}
+
+fn default_level() -> u8 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(deny_unknown_fields)]
+struct Point {
+    x: i32,
+    #[json(rename = "Y")]
+    y: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(rename_all = "snake_case")]
+enum Event {
+    KeyDown(String),
+    #[json(rename = "click")]
+    MouseClick {
+        #[json(default)]
+        button_id: u8,
+    },
+    WindowClosed,
+}
+
+fn parse<T: FromJsonOwned>(s: &str) -> Result<T, json::Err> {
+    T::from_json(&Value::from_str(s).unwrap())
+}
+
+#[test]
+fn test_rename() {
+    let user: User = parse(r#"{"userId": 1, "name": "a", "level": 3, "createdAt": "x"}"#).unwrap();
+    assert_eq!(1, user.user_id);
+    assert_eq!("a", user.display_name);
+    assert_eq!(3, user.level);
+    assert_eq!(
+        "missing field `userId`",
+        parse::<User>(r#"{"user_id": 1, "name": "a"}"#)
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn test_default_and_skip() {
+    let user: User = parse(r#"{"userId": 1, "name": "a", "cache": "c"}"#).unwrap();
+    assert_eq!(
+        User {
+            user_id: 1,
+            display_name: "a".to_string(),
+            tags: vec![],
+            level: 1,
+            cache: None,
+            email: None,
+            extra: Extra { created_at: None },
+        },
+        user
+    );
+
+    let mut user = user;
+    user.cache = Some("c".to_string());
+    assert_eq!(
+        value!({"userId": 1, "name": "a", "tags": [], "level": 1, "created_at": null}),
+        user.to_json()
+    );
+    user.email = Some("e".to_string());
+    assert_eq!(value!("e"), user.to_json()["email"]);
+}
+
+#[test]
+fn test_flatten() {
+    let user: User = parse(r#"{"userId": 1, "name": "a", "created_at": "today"}"#).unwrap();
+    assert_eq!(Some("today".to_string()), user.extra.created_at);
+    assert_eq!(value!("today"), user.to_json()["created_at"]);
+    assert_eq!(
+        "expected string, found number at /created_at",
+        parse::<User>(r#"{"userId": 1, "name": "a", "created_at": 1}"#)
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn test_deny_unknown_fields() {
+    assert_eq!(Ok(Point { x: 1, y: 2 }), parse(r#"{"x": 1, "Y": 2}"#));
+    assert_eq!(
+        "unknown field `y`",
+        parse::<Point>(r#"{"x": 1, "Y": 2, "y": 3}"#)
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(value!({"x": 1, "Y": 2}), Point { x: 1, y: 2 }.to_json());
+}
+
+#[test]
+fn test_enum() {
+    let cases = vec![
+        (Event::KeyDown("a".to_string()), value!({"key_down": "a"})),
+        (
+            Event::MouseClick { button_id: 2 },
+            value!({"click": {"button_id": 2}}),
+        ),
+        (Event::WindowClosed, value!("window_closed")),
+    ];
+    for (event, expected) in cases {
+        assert_eq!(expected, event.to_json());
+        assert_eq!(Ok(event), Event::from_json(&expected));
+    }
+    assert_eq!(
+        Ok(Event::MouseClick { button_id: 0 }),
+        parse(r#"{"click": {}}"#)
+    );
+    assert!(parse::<Event>(r#""WindowClosed""#).is_err());
+}
@@ -0,0 +1,142 @@
+use json::{value, Err, FromJson, FromJsonOwned, ToJson, Value};
+use serde_json::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::str::FromStr;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Wrapper<T> {
+    value: T,
+    items: Vec<T>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Pair<A, B>(A, B)
+where
+    A: Clone;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Borrowed<'a> {
+    name: &'a str,
+    tags: Vec<&'a str>,
+    alias: Option<&'a str>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Complex {
+    counts: std::collections::HashMap<String, u32>,
+    ip: [u8; 4],
+    pair: (i32, String),
+    celsius: Celsius,
+    children: Vec<Complex>,
+    next: Option<Box<Complex>>,
+}
+
+// implements the traits by hand.
+#[derive(Debug, PartialEq)]
+struct Celsius(f64);
+
+impl ToJson for Celsius {
+    fn to_json(&self) -> Value {
+        Value::from(format!("{}C", self.0))
+    }
+}
+
+impl<'a> FromJson<'a> for Celsius {
+    fn from_json(v: &'a Value) -> Result<Self, Err> {
+        let s: &str = FromJson::from_json(v)?;
+        s.trim_end_matches('C')
+            .parse()
+            .map(Celsius)
+            .map_err(|_| Err::invalid_type("temperature", "string"))
+    }
+}
+
+fn round_trip<T: ToJson + FromJsonOwned + Debug + PartialEq>(v: T, expected: Value) {
+    assert_eq!(expected, v.to_json());
+    let s = v.to_json().to_string();
+    assert_eq!(Ok(v), T::from_json(&Value::from_str(&s).unwrap()));
+}
+
+#[test]
+fn test_generics() {
+    round_trip(
+        Wrapper {
+            value: 1,
+            items: vec![2, 3],
+        },
+        value!({"value": 1, "items": [2, 3]}),
+    );
+    round_trip(
+        Wrapper {
+            value: Pair("a".to_string(), true),
+            items: vec![],
+        },
+        value!({"value": ["a", true], "items": []}),
+    );
+    round_trip(Either::<u8, String>::Left(1), value!({"Left": 1}));
+    round_trip(
+        Either::<u8, String>::Right("r".to_string()),
+        value!({"Right": "r"}),
+    );
+}
+
+#[test]
+fn test_borrowed() {
+    let v = Value::from_str(r#"{"name": "a", "tags": ["x", "y"]}"#).unwrap();
+    let b = Borrowed::from_json(&v).unwrap();
+    assert_eq!(
+        Borrowed {
+            name: "a",
+            tags: vec!["x", "y"],
+            alias: None,
+        },
+        b
+    );
+    assert_eq!(
+        value!({"name": "a", "tags": ["x", "y"], "alias": null}),
+        b.to_json()
+    );
+}
+
+#[test]
+fn test_complex() {
+    let v = Value::from_str(
+        r#"{
+            "counts": {"a": 1},
+            "ip": [127, 0, 0, 1],
+            "pair": [-1, "x"],
+            "celsius": "21.5C",
+            "children": [{
+                "counts": {},
+                "ip": [0, 0, 0, 0],
+                "pair": [0, ""],
+                "celsius": "0C",
+                "children": []
+            }],
+            "next": null
+        }"#,
+    )
+    .unwrap();
+    let c = Complex::from_json(&v).unwrap();
+    assert_eq!(Some(&1), c.counts.get("a"));
+    assert_eq!([127, 0, 0, 1], c.ip);
+    assert_eq!((-1, "x".to_string()), c.pair);
+    assert_eq!(Celsius(21.5), c.celsius);
+    assert_eq!(Celsius(0.0), c.children[0].celsius);
+    assert_eq!(value!("21.5C"), c.to_json()["celsius"]);
+    assert_eq!(Ok(&c), Complex::from_json(&c.to_json()).as_ref());
+
+    let v = value!({
+        "counts": {}, "ip": [1, 2, 3], "pair": [0, ""], "celsius": "0C", "children": []
+    });
+    assert_eq!(
+        "expected array of length 4, found array of length 3 at /ip",
+        Complex::from_json(&v).unwrap_err().to_string()
+    );
+}
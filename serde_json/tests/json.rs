@@ -1,25 +1,185 @@
-use serde_json::Deserialize;
+use json::{value, FromJsonOwned, ToJson, Value};
+use serde_json::{Deserialize, Serialize};
+use std::str::FromStr;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 struct Hoge {
     num: i64,
     str: String,
     bool: bool,
     vec: Vec<String>,
+    opt: Option<f64>,
     fuga: Fuga,
 }
 
-#[derive(Deserialize, Debug)]
-struct Fuga {}
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Fuga {
+    r#type: u8,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Newtype(String);
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Tuple(i32, Vec<bool>);
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Unit;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(i32, i32),
+    Rect { w: u32, h: u32 },
+    Group(Vec<Shape>),
+}
+
+fn parse<T: FromJsonOwned>(s: &str) -> Result<T, json::Err> {
+    T::from_json(&Value::from_str(s).unwrap())
+}
 
 #[test]
 fn test_hoge() {
+    let hoge: Hoge = parse(
+        r#"{
+            "num": -1,
+            "str": "hello",
+            "bool": true,
+            "vec": ["a", "b"],
+            "fuga": {"type": 3},
+            "unknown": null
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Hoge {
+            num: -1,
+            str: "hello".to_string(),
+            bool: true,
+            vec: vec!["a".to_string(), "b".to_string()],
+            opt: None,
+            fuga: Fuga { r#type: 3 },
+        },
+        hoge
+    );
+
+    let hoge: Hoge = parse(
+        r#"{"num": 0, "str": "", "bool": false, "vec": [], "opt": 1.5, "fuga": {"type": 0}}"#,
+    )
+    .unwrap();
+    assert_eq!(Some(1.5), hoge.opt);
+}
+
+#[test]
+fn test_tuple() {
+    assert_eq!(Ok(Newtype("a".to_string())), parse(r#""a""#));
+    assert_eq!(Ok(Tuple(1, vec![true])), parse("[1, [true]]"));
+    assert_eq!(Ok(Unit), parse("null"));
+    assert!(parse::<Tuple>("[1]").is_err());
+    assert!(parse::<Unit>("{}").is_err());
+}
+
+#[test]
+fn test_error() {
+    let cases = vec![
+        (
+            r#"{"str": "", "bool": true, "vec": [], "fuga": {"type": 1}}"#,
+            "missing field `num`",
+        ),
+        (
+            r#"{"num": "1", "str": "", "bool": true, "vec": [], "fuga": {"type": 1}}"#,
+            "expected number, found string at /num",
+        ),
+        (
+            r#"{"num": 1, "str": "", "bool": true, "vec": [1], "fuga": {"type": 1}}"#,
+            "expected string, found number at /vec/0",
+        ),
+        (
+            r#"{"num": 1, "str": "", "bool": true, "vec": [], "fuga": {"type": 256}}"#,
+            "number out of range for u8 at /fuga/type",
+        ),
+        (
+            r#"{"num": 1, "str": "", "bool": true, "vec": [], "fuga": {}}"#,
+            "missing field `type` at /fuga",
+        ),
+        ("[]", "expected object, found array"),
+    ];
+    for case in cases {
+        assert_eq!(case.1, parse::<Hoge>(case.0).unwrap_err().to_string());
+    }
+    assert_eq!(
+        "expected array of length 2, found object",
+        parse::<Tuple>("{}").unwrap_err().to_string()
+    );
+    assert_eq!(
+        "expected array, found boolean at /1",
+        parse::<Tuple>("[1, true]").unwrap_err().to_string()
+    );
+}
+
+fn round_trip<T: ToJson + FromJsonOwned + std::fmt::Debug + PartialEq>(v: T, expected: Value) {
+    assert_eq!(expected, v.to_json());
+    let s = v.to_json().to_string();
+    assert_eq!(Ok(v), parse(&s));
+}
+
+#[test]
+fn test_serialize() {
     let hoge = Hoge {
-        num: 0,
+        num: -1,
         str: "hello".to_string(),
-        bool: false,
-        vec: vec!["hello".to_string()],
-        fuga: Fuga {},
+        bool: true,
+        vec: vec!["a".to_string()],
+        opt: Some(0.5),
+        fuga: Fuga { r#type: 3 },
     };
-    hoge.foo();
+    round_trip(
+        hoge,
+        value!({
+            "num": -1,
+            "str": "hello",
+            "bool": true,
+            "vec": ["a"],
+            "opt": 0.5,
+            "fuga": {"type": 3}
+        }),
+    );
+    round_trip(Newtype("a".to_string()), value!("a"));
+    round_trip(Tuple(1, vec![false]), value!([1, [false]]));
+    round_trip(Unit, value!(null));
+    assert_eq!(r#"{"type":3}"#, Fuga { r#type: 3 }.to_json().to_string());
+}
+
+#[test]
+fn test_enum() {
+    round_trip(Shape::Empty, value!("Empty"));
+    round_trip(Shape::Circle(1.5), value!({"Circle": 1.5}));
+    round_trip(Shape::Line(1, -1), value!({"Line": [1, -1]}));
+    round_trip(
+        Shape::Rect { w: 2, h: 3 },
+        value!({"Rect": {"w": 2, "h": 3}}),
+    );
+    round_trip(
+        Shape::Group(vec![Shape::Empty, Shape::Group(vec![])]),
+        value!({"Group": ["Empty", {"Group": []}]}),
+    );
+    assert_eq!(Ok(Shape::Empty), parse(r#"{"Empty": null}"#));
+
+    let cases = vec![
+        (r#""Square""#, "unknown variant `Square`"),
+        (r#""Circle""#, "unknown variant `Circle`"),
+        (
+            r#"{"Circle": 1, "Empty": null}"#,
+            "expected string or object with a single member, found object",
+        ),
+        (r#"{"Rect": {"w": 1}}"#, "missing field `h` at /Rect"),
+        (
+            r#"{"Group": [{"Line": [1]}]}"#,
+            "expected array of length 2, found array at /Group/0/Line",
+        ),
+    ];
+    for case in cases {
+        assert_eq!(case.1, parse::<Shape>(case.0).unwrap_err().to_string());
+    }
 }
@@ -0,0 +1,154 @@
+use json::{value, FromJsonOwned, ToJson, Value};
+use serde_json::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::str::FromStr;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(tag = "type")]
+enum Internal {
+    Unit,
+    Newtype(Point),
+    Struct { id: u32 },
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(tag = "t", content = "c", rename_all = "lowercase")]
+enum Adjacent {
+    Unit,
+    Newtype(String),
+    Tuple(i32, bool),
+    Struct { id: u32 },
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(untagged)]
+enum Untagged {
+    Unit,
+    Int(i64),
+    Pair(String, String),
+    Point(Point),
+    Struct { id: u32, name: String },
+}
+
+fn round_trip<T: ToJson + FromJsonOwned + Debug + PartialEq>(v: T, expected: Value) {
+    assert_eq!(expected, v.to_json());
+    let s = v.to_json().to_string();
+    assert_eq!(Ok(v), T::from_json(&Value::from_str(&s).unwrap()));
+}
+
+fn parse_err<T: FromJsonOwned + Debug>(s: &str) -> String {
+    T::from_json(&Value::from_str(s).unwrap())
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_internal() {
+    round_trip(Internal::Unit, value!({"type": "Unit"}));
+    round_trip(
+        Internal::Newtype(Point { x: 1, y: 2 }),
+        value!({"type": "Newtype", "x": 1, "y": 2}),
+    );
+    round_trip(
+        Internal::Struct { id: 7 },
+        value!({"type": "Struct", "id": 7}),
+    );
+
+    let cases = vec![
+        (r#"{"id": 7}"#, "missing field `type`"),
+        (r#"{"type": 1}"#, "expected string, found number at /type"),
+        (r#"{"type": "Other"}"#, "unknown variant `Other`"),
+        (r#"{"type": "Newtype", "x": 1}"#, "missing field `y`"),
+        (r#""Unit""#, "expected object, found string"),
+    ];
+    for case in cases {
+        assert_eq!(case.1, parse_err::<Internal>(case.0));
+    }
+}
+
+// an alias hides from the derive that the content isn't a struct.
+type Count = u32;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[json(tag = "type")]
+enum Aliased {
+    Count(Count),
+    Point(Point),
+}
+
+#[test]
+fn test_internal_non_object() {
+    round_trip(Aliased::Count(5), value!({"type": "Count", "value": 5}));
+    round_trip(
+        Aliased::Point(Point { x: 1, y: 2 }),
+        value!({"type": "Point", "x": 1, "y": 2}),
+    );
+    assert_eq!(
+        "expected number, found string at /value",
+        parse_err::<Aliased>(r#"{"type": "Count", "value": "5"}"#)
+    );
+    assert_eq!(
+        "expected number, found object",
+        parse_err::<Aliased>(r#"{"type": "Count", "count": 5}"#)
+    );
+}
+
+#[test]
+fn test_adjacent() {
+    round_trip(Adjacent::Unit, value!({"t": "unit"}));
+    round_trip(
+        Adjacent::Newtype("a".to_string()),
+        value!({"t": "newtype", "c": "a"}),
+    );
+    round_trip(
+        Adjacent::Tuple(1, true),
+        value!({"t": "tuple", "c": [1, true]}),
+    );
+    round_trip(
+        Adjacent::Struct { id: 7 },
+        value!({"t": "struct", "c": {"id": 7}}),
+    );
+
+    let cases = vec![
+        (r#"{"t": "newtype"}"#, "missing field `c`"),
+        (
+            r#"{"t": "tuple", "c": [1, 2]}"#,
+            "expected boolean, found number at /c/1",
+        ),
+        (r#"{"t": "Unit"}"#, "unknown variant `Unit`"),
+    ];
+    for case in cases {
+        assert_eq!(case.1, parse_err::<Adjacent>(case.0));
+    }
+}
+
+#[test]
+fn test_untagged() {
+    round_trip(Untagged::Unit, value!(null));
+    round_trip(Untagged::Int(-3), value!(-3));
+    round_trip(
+        Untagged::Pair("a".to_string(), "b".to_string()),
+        value!(["a", "b"]),
+    );
+    round_trip(
+        Untagged::Point(Point { x: 1, y: 2 }),
+        value!({"x": 1, "y": 2}),
+    );
+    round_trip(
+        Untagged::Struct {
+            id: 1,
+            name: "a".to_string(),
+        },
+        value!({"id": 1, "name": "a"}),
+    );
+    assert_eq!(
+        "no variant of Untagged matches",
+        parse_err::<Untagged>(r#"{"id": 1}"#)
+    );
+}
@@ -0,0 +1,6 @@
+// the derives reject bad input with a compile error rather than a panic.
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
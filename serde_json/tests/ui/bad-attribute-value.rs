@@ -0,0 +1,21 @@
+use serde_json::Deserialize;
+
+#[derive(Deserialize)]
+struct S {
+    #[json(rename = 1)]
+    a: u32,
+}
+
+#[derive(Deserialize)]
+struct T {
+    #[json(skip = "yes")]
+    a: u32,
+}
+
+#[derive(Deserialize)]
+struct U {
+    #[json(default, default)]
+    a: u32,
+}
+
+fn main() {}
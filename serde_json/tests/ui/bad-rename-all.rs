@@ -0,0 +1,9 @@
+use serde_json::Serialize;
+
+#[derive(Serialize)]
+#[json(rename_all = "camel")]
+struct S {
+    a_b: u32,
+}
+
+fn main() {}
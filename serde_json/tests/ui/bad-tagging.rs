@@ -0,0 +1,58 @@
+use serde_json::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+#[json(tag = "type")]
+struct S {
+    a: u32,
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Internal {
+    Tuple(u32, u32),
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Primitive {
+    Point { x: i32 },
+    Number(i32),
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Text {
+    Name(&'static str),
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Dynamic {
+    Any(json::Value),
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Generic<T> {
+    Param(T),
+}
+
+#[derive(Serialize)]
+#[json(tag = "type")]
+enum Container {
+    List(Vec<u32>),
+}
+
+#[derive(Serialize)]
+#[json(content = "c")]
+enum Content {
+    A(u32),
+}
+
+#[derive(Serialize)]
+#[json(untagged, tag = "type")]
+enum Untagged {
+    A(u32),
+}
+
+fn main() {}
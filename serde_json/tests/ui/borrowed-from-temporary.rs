@@ -0,0 +1,15 @@
+use json::{FromJsonOwned, Value};
+use serde_json::Deserialize;
+
+#[derive(Deserialize)]
+struct Borrowed<'a> {
+    name: &'a str,
+}
+
+fn parse<T: FromJsonOwned>(v: Value) -> T {
+    T::from_json(&v).unwrap()
+}
+
+fn main() {
+    let _: Borrowed = parse(Value::Null);
+}
@@ -0,0 +1,13 @@
+use serde_json::Deserialize;
+
+#[derive(Deserialize)]
+#[json(deny_unknown_fields)]
+struct S {
+    #[json(flatten)]
+    inner: T,
+}
+
+#[derive(Deserialize)]
+struct T {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use serde_json::Deserialize;
+
+#[derive(Deserialize)]
+struct S(#[json(rename = "a")] u32);
+
+fn main() {}
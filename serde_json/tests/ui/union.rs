@@ -0,0 +1,8 @@
+use serde_json::Serialize;
+
+#[derive(Serialize)]
+union U {
+    a: u32,
+}
+
+fn main() {}
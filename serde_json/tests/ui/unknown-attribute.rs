@@ -0,0 +1,9 @@
+use serde_json::Deserialize;
+
+#[derive(Deserialize)]
+struct S {
+    #[json(renamed = "a")]
+    a: u32,
+}
+
+fn main() {}
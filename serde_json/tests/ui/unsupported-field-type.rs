@@ -0,0 +1,8 @@
+use serde_json::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+struct S {
+    file: std::fs::File,
+}
+
+fn main() {}
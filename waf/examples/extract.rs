@@ -0,0 +1,27 @@
+use serde::Deserialize;
+use waf::{extract, text, Json, Path, Router, Server, State};
+
+#[derive(Deserialize)]
+struct Greeting {
+    name: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let mut router = Router::new();
+    router.state("Hello".to_string());
+    router.get(
+        "/users/:id",
+        extract(|Path(id): Path<u64>| async move { text(format!("user {}", id)) }),
+    );
+    router.post(
+        "/greet",
+        extract(
+            |State(greeting): State<String>, Json(body): Json<Greeting>| async move {
+                text(format!("{}, {}!", greeting, body.name))
+            },
+        ),
+    );
+
+    Server::new().serve(router, 3000).await.unwrap();
+}
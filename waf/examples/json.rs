@@ -1,19 +1,14 @@
-extern crate hyper;
-
 #[macro_use]
 extern crate serde_json;
 
-use hyper::{Body, Response, StatusCode};
-use waf::{Router, Server};
+use waf::{json, Router, Server};
 
-fn main() {
+#[tokio::main]
+async fn main() {
     let mut router = Router::new();
-    router.get("/json", |_, _| {
-        Response::builder()
-            .status(StatusCode::OK)
-            .header("Content-Type", "application/json")
-            .body(Body::from(json!({"message": "Hello, World!"}).to_string()))
+    router.get("/json", |_, _| async {
+        json(&json!({"message": "Hello, World!"}))
     });
 
-    Server::new().serve(router, 3000);
+    Server::new().serve(router, 3000).await.unwrap();
 }
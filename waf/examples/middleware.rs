@@ -3,39 +3,43 @@ extern crate hyper;
 #[macro_use]
 extern crate serde_json;
 
-use hyper::{Body, Response, StatusCode};
-use waf::{Router, Server};
+use hyper::header::HeaderValue;
+use hyper::StatusCode;
+use std::time::Instant;
+use waf::{json, status, Router, Server};
 
-fn main() {
+#[tokio::main]
+async fn main() {
     let mut router = Router::new();
     router
-        .middleware("/users/*", |ctx, req| {
-            match req.headers().get("Authorization") {
-                Some(val) => {
-                    let val = val.to_str().unwrap().to_string();
-                    if val.starts_with("Bearer ") {
-                        ctx.set_value("username", "John".to_string());
-                    }
+        .middleware("/*", |ctx, req, next| async move {
+            let start = Instant::now();
+            let mut res = next.run(ctx, req).await;
+            let elapsed = format!("{}us", start.elapsed().as_micros());
+            res.headers_mut()
+                .insert("X-Response-Time", HeaderValue::from_str(&elapsed).unwrap());
+            res
+        })
+        .middleware("/users/*", |mut ctx, req, next| async move {
+            let token = req
+                .headers()
+                .get("Authorization")
+                .and_then(|val| val.to_str().ok())
+                .filter(|val| val.starts_with("Bearer "));
+            match token {
+                Some(_) => {
+                    ctx.set_value("username", "John".to_string());
+                    next.run(ctx, req).await
                 }
-                None => (),
+                None => status(StatusCode::UNAUTHORIZED),
             }
-            Ok(())
         })
-        .get("/users/:id", |ctx, _| {
-            let unknown = "unknown".to_string();
-            let username: &String = match ctx.value("username") {
-                Some(val) => val,
-                None => &unknown,
-            };
+        .get("/users/:id", |ctx, _| async move {
+            let username: &String = ctx.value("username").unwrap();
             let id = ctx.param("id");
 
-            Response::builder()
-                .status(StatusCode::OK)
-                .header("Content-Type", "application/json")
-                .body(Body::from(
-                    json!({ "id": id, "username": username }).to_string(),
-                ))
+            json(&json!({ "id": id, "username": username }))
         });
 
-    Server::new().serve(router, 3000);
+    Server::new().serve(router, 3000).await.unwrap();
 }
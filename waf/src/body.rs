@@ -0,0 +1,120 @@
+use bytes::Bytes;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Empty, Full, LengthLimitError, Limited};
+use hyper::body::{Body as HttpBody, Frame, SizeHint};
+use hyper::StatusCode;
+use serde::de::DeserializeOwned;
+use std::error;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::error::Error;
+
+pub type BoxError = Box<dyn error::Error + Send + Sync>;
+
+// the most bytes the extractors read from a request body.
+pub const DEFAULT_LIMIT: usize = 2 * 1024 * 1024;
+
+// the body of a request or response: a stream from the connection or bytes in memory.
+pub struct Body(UnsyncBoxBody<Bytes, BoxError>);
+
+impl Body {
+    pub fn new<B>(body: B) -> Self
+    where
+        B: HttpBody<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Body(body.map_err(Into::into).boxed_unsync())
+    }
+
+    pub fn empty() -> Self {
+        Self::new(Empty::new())
+    }
+
+    // reads the whole body. a body of more than `limit` bytes is a 413 Payload Too Large.
+    pub async fn bytes(self, limit: usize) -> Result<Bytes, Error> {
+        match Limited::new(self, limit).collect().await {
+            Ok(collected) => Ok(collected.to_bytes()),
+            Err(e) if e.is::<LengthLimitError>() => Err(Error::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("body larger than {} bytes", limit),
+            )),
+            Err(e) => Err(Error::bad_request(format!("failed to read body: {}", e))),
+        }
+    }
+
+    pub async fn text(self, limit: usize) -> Result<String, Error> {
+        let bs = self.bytes(limit).await?;
+        String::from_utf8(bs.to_vec())
+            .map_err(|e| Error::bad_request(format!("invalid UTF-8 body: {}", e)))
+    }
+
+    pub async fn json<T: DeserializeOwned>(self, limit: usize) -> Result<T, Error> {
+        let bs = self.bytes(limit).await?;
+        serde_json::from_slice(&bs)
+            .map_err(|e| Error::bad_request(format!("invalid JSON body: {}", e)))
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl HttpBody for Body {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, BoxError>>> {
+        Pin::new(&mut self.0).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.0.size_hint()
+    }
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Body")
+    }
+}
+
+impl From<Bytes> for Body {
+    fn from(bs: Bytes) -> Self {
+        Self::new(Full::new(bs))
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bs: Vec<u8>) -> Self {
+        Self::from(Bytes::from(bs))
+    }
+}
+
+impl From<&'static [u8]> for Body {
+    fn from(bs: &'static [u8]) -> Self {
+        Self::from(Bytes::from_static(bs))
+    }
+}
+
+impl From<String> for Body {
+    fn from(s: String) -> Self {
+        Self::from(Bytes::from(s))
+    }
+}
+
+impl From<&'static str> for Body {
+    fn from(s: &'static str) -> Self {
+        Self::from(Bytes::from_static(s.as_bytes()))
+    }
+}
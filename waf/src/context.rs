@@ -1,17 +1,29 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::sync::Arc;
+
+pub(crate) type States = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
 
 #[derive(Debug)]
-pub struct Context<'a> {
-    params: Option<Vec<(&'a str, &'a str)>>,
-    values: HashMap<String, Box<dyn Any>>,
+pub struct Context {
+    params: Option<Vec<(String, String)>>,
+    values: HashMap<String, Box<dyn Any + Send + Sync>>,
+    // the states of the router, shared by all requests.
+    states: Arc<States>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<'a> Context<'a> {
+impl Context {
     pub fn new() -> Self {
         Self {
             params: None,
             values: HashMap::new(),
+            states: Arc::new(HashMap::new()),
         }
     }
 
@@ -23,7 +35,7 @@ impl<'a> Context<'a> {
             Some(params) => {
                 let id = id.into();
                 for (k, v) in params.iter() {
-                    if id == *k {
+                    if id == k {
                         return Some(v);
                     }
                 }
@@ -33,7 +45,11 @@ impl<'a> Context<'a> {
         }
     }
 
-    pub fn set_params(&mut self, params: Vec<(&'a str, &'a str)>) {
+    pub fn params(&self) -> &[(String, String)] {
+        self.params.as_deref().unwrap_or(&[])
+    }
+
+    pub fn set_params(&mut self, params: Vec<(String, String)>) {
         self.params = Some(params);
     }
 
@@ -51,8 +67,23 @@ impl<'a> Context<'a> {
     pub fn set_value<S, T>(&mut self, id: S, val: T)
     where
         S: Into<String>,
-        T: 'static,
+        T: Send + Sync + 'static,
     {
         self.values.insert(id.into(), Box::new(val));
     }
+
+    // the state of type `T` added with `Router::state`.
+    pub fn state<T>(&self) -> Option<&T>
+    where
+        T: 'static,
+    {
+        match self.states.get(&TypeId::of::<T>()) {
+            Some(state) => (**state).downcast_ref::<T>(),
+            None => None,
+        }
+    }
+
+    pub(crate) fn set_states(&mut self, states: Arc<States>) {
+        self.states = states;
+    }
 }
@@ -0,0 +1,101 @@
+use hyper::header::{self, HeaderValue};
+use hyper::{http, StatusCode};
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::body::{Body, BoxError};
+use super::response::IntoResponse;
+use super::Response;
+
+// an error of a handler or an extractor, answered with its status and message.
+// other error types map onto a status with a `From` impl, which lets handlers use `?`.
+#[derive(Debug)]
+pub struct Error {
+    status: StatusCode,
+    message: String,
+    source: Option<BoxError>,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(status: StatusCode, message: S) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn bad_request<S: Into<String>>(message: S) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found<S: Into<String>>(message: S) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    // a 500 Internal Server Error. the cause is kept for logging, but not sent to the
+    // client.
+    pub fn internal<E: Into<BoxError>>(source: E) -> Self {
+        let status = StatusCode::INTERNAL_SERVER_ERROR;
+        Self {
+            status,
+            message: status.canonical_reason().unwrap_or("").to_string(),
+            source: Some(source.into()),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}: {}", self.status, source),
+            None => write!(f, "{}: {}", self.status, self.message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(&**source),
+            None => None,
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    // the cause of a server error only goes to the log, since the client sees the message.
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            eprintln!("{}", self);
+        }
+        let mut res = Response::new(Body::from(self.message));
+        *res.status_mut() = self.status;
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=utf-8"),
+        );
+        res
+    }
+}
+
+impl From<http::Error> for Error {
+    fn from(e: http::Error) -> Self {
+        Self::internal(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::internal(e)
+    }
+}
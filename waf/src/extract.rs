@@ -0,0 +1,240 @@
+use bytes::Bytes;
+use hyper::header::{self, HeaderValue};
+use hyper::StatusCode;
+use serde::de::DeserializeOwned;
+use std::any;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::body::DEFAULT_LIMIT;
+use super::context::Context;
+use super::error::Error;
+use super::handler::BoxFuture;
+use super::params::from_params;
+use super::response::IntoResponse;
+use super::{Request, Response};
+
+pub type ExtractFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+// a handler parameter built from the request. extractors run in the order of the
+// parameters, and a body extractor like `Json` takes the body for itself. a failed
+// extractor answers the request with its error.
+pub trait FromRequest: Sized + Send + 'static {
+    fn from_request<'a>(ctx: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self>;
+}
+
+// the parameters of the route: the only one, like `id` in `/users/:id`, or all of them
+// as a tuple in the order of the route or as a struct with a field for each.
+#[derive(Debug)]
+pub struct Path<T>(pub T);
+
+impl<T> FromRequest for Path<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_request<'a>(ctx: &'a Context, _: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(async move { from_params(ctx.params()).map(Path) })
+    }
+}
+
+// the query string, deserialized from `application/x-www-form-urlencoded`.
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+impl<T> FromRequest for Query<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_request<'a>(_: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(async move {
+            serde_urlencoded::from_str(req.uri().query().unwrap_or(""))
+                .map(Query)
+                .map_err(|e| Error::bad_request(format!("invalid query string: {}", e)))
+        })
+    }
+}
+
+// the body, deserialized from JSON.
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+impl<T> FromRequest for Json<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_request<'a>(_: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(async move {
+            let is_json = req
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|val| val.to_str().ok())
+                .is_some_and(|val| {
+                    // the media type without its parameters, like `; charset=utf-8`.
+                    let media_type = val.split(';').next().unwrap_or("").trim();
+                    media_type.eq_ignore_ascii_case("application/json")
+                });
+            if !is_json {
+                return Err(Error::new(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "expected Content-Type: application/json",
+                ));
+            }
+            let body = mem::take(req.body_mut());
+            body.json(DEFAULT_LIMIT).await.map(Json)
+        })
+    }
+}
+
+// the body as bytes, up to `DEFAULT_LIMIT`.
+impl FromRequest for Bytes {
+    fn from_request<'a>(_: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(mem::take(req.body_mut()).bytes(DEFAULT_LIMIT))
+    }
+}
+
+// the body as UTF-8 text, up to `DEFAULT_LIMIT`.
+impl FromRequest for String {
+    fn from_request<'a>(_: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(mem::take(req.body_mut()).text(DEFAULT_LIMIT))
+    }
+}
+
+// a request header parsed into a type.
+pub trait FromHeader: Sized {
+    // the lowercase header name.
+    const NAME: &'static str;
+
+    fn from_header(value: &HeaderValue) -> Result<Self, String>;
+}
+
+// a required header, like `Header<UserAgent>`.
+#[derive(Debug)]
+pub struct Header<T>(pub T);
+
+impl<T> FromRequest for Header<T>
+where
+    T: FromHeader + Send + 'static,
+{
+    fn from_request<'a>(_: &'a Context, req: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(async move {
+            match req.headers().get(T::NAME) {
+                Some(val) => T::from_header(val).map(Header).map_err(|e| {
+                    Error::bad_request(format!("invalid header `{}`: {}", T::NAME, e))
+                }),
+                None => Err(Error::bad_request(format!("missing header `{}`", T::NAME))),
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct UserAgent(pub String);
+
+impl FromHeader for UserAgent {
+    const NAME: &'static str = "user-agent";
+
+    fn from_header(value: &HeaderValue) -> Result<Self, String> {
+        header_str(value).map(|s| UserAgent(s.to_string()))
+    }
+}
+
+// the token of an `Authorization: Bearer <token>` header.
+#[derive(Debug)]
+pub struct Bearer(pub String);
+
+impl FromHeader for Bearer {
+    const NAME: &'static str = "authorization";
+
+    fn from_header(value: &HeaderValue) -> Result<Self, String> {
+        // the scheme is case-insensitive.
+        match header_str(value)?.split_once(' ') {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => {
+                match token.trim_start() {
+                    "" => Err("expected a bearer token".to_string()),
+                    token => Ok(Bearer(token.to_string())),
+                }
+            }
+            _ => Err("expected a bearer token".to_string()),
+        }
+    }
+}
+
+fn header_str(value: &HeaderValue) -> Result<&str, String> {
+    value.to_str().map_err(|e| e.to_string())
+}
+
+// a clone of the state added with `Router::state`.
+#[derive(Debug)]
+pub struct State<T>(pub T);
+
+impl<T> FromRequest for State<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn from_request<'a>(ctx: &'a Context, _: &'a mut Request) -> ExtractFuture<'a, Self> {
+        Box::pin(async move {
+            match ctx.state::<T>() {
+                Some(state) => Ok(State(state.clone())),
+                None => Err(Error::internal(format!(
+                    "no state of type {}",
+                    any::type_name::<T>()
+                ))),
+            }
+        })
+    }
+}
+
+// a handler whose parameters are all extractors.
+pub trait ExtractHandler<Args>: Send + Sync + 'static {
+    fn call(self: Arc<Self>, ctx: Context, req: Request) -> BoxFuture<Response>;
+}
+
+macro_rules! impl_extract_handler {
+    ( $( ( $( $a:ident ),+ ) )* ) => {
+        $(
+            impl<F, Fut, $( $a ),+> ExtractHandler<( $( $a, )+ )> for F
+            where
+                F: Fn( $( $a ),+ ) -> Fut + Send + Sync + 'static,
+                Fut: Future + Send + 'static,
+                Fut::Output: IntoResponse,
+                $( $a: FromRequest ),+
+            {
+                #[allow(non_snake_case)]
+                fn call(self: Arc<Self>, ctx: Context, mut req: Request) -> BoxFuture<Response> {
+                    Box::pin(async move {
+                        $(
+                            let $a = match $a::from_request(&ctx, &mut req).await {
+                                Ok(v) => v,
+                                Err(e) => return e.into_response(),
+                            };
+                        )+
+                        self( $( $a ),+ ).await.into_response()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_extract_handler! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, G)
+}
+
+// adapts a handler taking extractors, answering with the error of the first one which
+// fails.
+pub fn extract<H, Args>(
+    handler: H,
+) -> impl Fn(Context, Request) -> BoxFuture<Response> + Send + Sync + 'static
+where
+    H: ExtractHandler<Args>,
+{
+    let handler = Arc::new(handler);
+    move |ctx: Context, req: Request| handler.clone().call(ctx, req)
+}
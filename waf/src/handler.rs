@@ -0,0 +1,48 @@
+use std::future::Future;
+use std::panic;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::context::Context;
+use super::response::IntoResponse;
+use super::{Request, Response};
+
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+pub(crate) trait Handler: Send + Sync + 'static {
+    fn call(&self, ctx: Context, req: Request) -> BoxFuture<Response>;
+}
+
+impl<F, Fut> Handler for F
+where
+    F: Fn(Context, Request) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: IntoResponse,
+{
+    fn call(&self, ctx: Context, req: Request) -> BoxFuture<Response> {
+        let fut = self(ctx, req);
+        Box::pin(async move { fut.await.into_response() })
+    }
+}
+
+// adapts a synchronous handler. it runs on the blocking thread pool, so a slow call
+// doesn't hold up the other requests.
+pub fn blocking<H, R>(
+    handler: H,
+) -> impl Fn(Context, Request) -> BoxFuture<Response> + Send + Sync + 'static
+where
+    H: Fn(&Context, &Request) -> R + Send + Sync + 'static,
+    R: IntoResponse + Send + 'static,
+{
+    let handler = Arc::new(handler);
+    move |ctx: Context, req: Request| -> BoxFuture<Response> {
+        let handler = handler.clone();
+        Box::pin(async move {
+            match tokio::task::spawn_blocking(move || handler(&ctx, &req)).await {
+                Ok(res) => res.into_response(),
+                // the task is never cancelled, so it panicked.
+                Err(e) => panic::resume_unwind(e.into_panic()),
+            }
+        })
+    }
+}
@@ -1,7 +1,26 @@
+mod body;
 mod context;
+mod error;
+mod extract;
+mod handler;
+mod middleware;
+mod params;
+mod response;
 mod router;
 mod server;
 
+pub use body::{Body, BoxError, DEFAULT_LIMIT};
 pub use context::Context;
+pub use error::Error;
+pub use extract::{
+    extract, Bearer, ExtractFuture, ExtractHandler, FromHeader, FromRequest, Header, Json, Path,
+    Query, State, UserAgent,
+};
+pub use handler::{blocking, BoxFuture};
+pub use middleware::Next;
+pub use response::{html, json, permanent_redirect, redirect, status, text, IntoResponse};
 pub use router::Router;
 pub use server::Server;
+
+pub type Request = hyper::Request<Body>;
+pub type Response = hyper::Response<Body>;
@@ -0,0 +1,52 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use super::context::Context;
+use super::handler::{BoxFuture, Handler};
+use super::response::IntoResponse;
+use super::{Request, Response};
+
+pub(crate) trait Middleware: Send + Sync + 'static {
+    fn call(&self, ctx: Context, req: Request, next: Next) -> BoxFuture<Response>;
+}
+
+impl<F, Fut> Middleware for F
+where
+    F: Fn(Context, Request, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: IntoResponse,
+{
+    fn call(&self, ctx: Context, req: Request, next: Next) -> BoxFuture<Response> {
+        let fut = self(ctx, req, next);
+        Box::pin(async move { fut.await.into_response() })
+    }
+}
+
+// the rest of the chain after a middleware: the middlewares registered after it, then the
+// handler of the route.
+pub struct Next {
+    middlewares: Arc<[Arc<dyn Middleware>]>,
+    index: usize,
+    endpoint: Arc<dyn Handler>,
+}
+
+impl Next {
+    pub(crate) fn new(middlewares: Arc<[Arc<dyn Middleware>]>, endpoint: Arc<dyn Handler>) -> Self {
+        Self {
+            middlewares,
+            index: 0,
+            endpoint,
+        }
+    }
+
+    // runs the rest of the chain and returns its response.
+    pub fn run(mut self, ctx: Context, req: Request) -> BoxFuture<Response> {
+        match self.middlewares.get(self.index).cloned() {
+            Some(middleware) => {
+                self.index += 1;
+                middleware.call(ctx, req, self)
+            }
+            None => self.endpoint.call(ctx, req),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+use std::slice;
+
+use super::error::Error;
+
+// deserializes the parameters of a route, like `id` in `/users/:id`: a single one into a
+// scalar, all of them into a tuple by position, or into a struct by name.
+pub(crate) fn from_params<T: DeserializeOwned>(params: &[(String, String)]) -> Result<T, Error> {
+    T::deserialize(Params(params)).map_err(|e| match e {
+        ParamError::Invalid(..) => Error::bad_request(e.to_string()),
+        // the type doesn't fit the route, which is a bug of the server.
+        ParamError::Shape(msg) => Error::internal(format!("path parameters: {}", msg)),
+    })
+}
+
+#[derive(Debug)]
+enum ParamError {
+    // a parameter which the client got wrong.
+    Invalid(String, String),
+    Shape(String),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Invalid(name, msg) => {
+                write!(f, "invalid path parameter `{}`: {}", name, msg)
+            }
+            ParamError::Shape(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl de::Error for ParamError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ParamError::Shape(msg.to_string())
+    }
+}
+
+struct Params<'a>(&'a [(String, String)]);
+
+impl<'a> Params<'a> {
+    fn single(&self) -> Result<Param<'a>, ParamError> {
+        match self.0 {
+            [(name, value)] => Ok(Param { name, value }),
+            params => Err(ParamError::Shape(format!(
+                "expected 1 parameter, found {}",
+                params.len()
+            ))),
+        }
+    }
+
+    fn visit_tuple<'de, V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        if len != self.0.len() {
+            return Err(ParamError::Shape(format!(
+                "expected {} parameters, found {}",
+                len,
+                self.0.len()
+            )));
+        }
+        visitor.visit_seq(Seq(self.0.iter()))
+    }
+}
+
+macro_rules! forward_to_single {
+    ( $( $method:ident )* ) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Params<'a> {
+    type Error = ParamError;
+
+    forward_to_single! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        self.single()?.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_seq(Seq(self.0.iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        self.visit_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        self.visit_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_map(Map {
+            iter: self.0.iter(),
+            next: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+}
+
+struct Seq<'a>(slice::Iter<'a, (String, String)>);
+
+impl<'de, 'a> de::SeqAccess<'de> for Seq<'a> {
+    type Error = ParamError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, ParamError> {
+        self.0
+            .next()
+            .map(|(name, value)| seed.deserialize(Param { name, value }))
+            .transpose()
+    }
+}
+
+struct Map<'a> {
+    iter: slice::Iter<'a, (String, String)>,
+    next: Option<Param<'a>>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Map<'a> {
+    type Error = ParamError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ParamError> {
+        match self.iter.next() {
+            Some((name, value)) => {
+                self.next = Some(Param { name, value });
+                seed.deserialize(name.as_str().into_deserializer())
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, ParamError> {
+        match self.next.take() {
+            Some(param) => seed.deserialize(param),
+            None => Err(ParamError::Shape("value without a key".to_string())),
+        }
+    }
+}
+
+// a single parameter, parsed from its text into the type asked for.
+struct Param<'a> {
+    name: &'a str,
+    value: &'a str,
+}
+
+impl<'a> Param<'a> {
+    // the errors while reading a parameter come from its text.
+    fn invalid(&self, e: ParamError) -> ParamError {
+        match e {
+            ParamError::Shape(msg) => ParamError::Invalid(self.name.to_string(), msg),
+            e => e,
+        }
+    }
+}
+
+macro_rules! parse_param {
+    ( $( $method:ident => $visit:ident, )* ) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+                match self.value.parse() {
+                    Ok(v) => visitor.$visit(v).map_err(|e| self.invalid(e)),
+                    Err(e) => Err(ParamError::Invalid(self.name.to_string(), format!("{}", e))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Param<'a> {
+    type Error = ParamError;
+
+    parse_param! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_str(self.value).map_err(|e| self.invalid(e))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // only unit variants, named by the parameter.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParamError> {
+        let value: de::value::StrDeserializer<'_, ParamError> = self.value.into_deserializer();
+        visitor.visit_enum(value).map_err(|e| self.invalid(e))
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
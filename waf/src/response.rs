@@ -0,0 +1,121 @@
+use hyper::header::{self, HeaderValue};
+use hyper::{http, StatusCode};
+use serde::Serialize;
+use std::convert::TryFrom;
+
+use super::body::Body;
+use super::error::Error;
+use super::extract::Json;
+use super::Response;
+
+// the return value of a handler. a `Result` answers with either side, so handlers can
+// return their own error types as long as they convert too.
+pub trait IntoResponse {
+    fn into_response(self) -> Response;
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> Response {
+        self
+    }
+}
+
+impl<T, E> IntoResponse for Result<T, E>
+where
+    T: IntoResponse,
+    E: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        match self {
+            Ok(res) => res.into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
+}
+
+// a response which failed to build.
+impl IntoResponse for http::Error {
+    fn into_response(self) -> Response {
+        Error::from(self).into_response()
+    }
+}
+
+impl IntoResponse for StatusCode {
+    fn into_response(self) -> Response {
+        status(self)
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Response {
+        text(self)
+    }
+}
+
+impl IntoResponse for &'static str {
+    fn into_response(self) -> Response {
+        text(self)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        json(&self.0).into_response()
+    }
+}
+
+// replaces the status of the response of `T`.
+impl<T: IntoResponse> IntoResponse for (StatusCode, T) {
+    fn into_response(self) -> Response {
+        let mut res = self.1.into_response();
+        *res.status_mut() = self.0;
+        res
+    }
+}
+
+// a 200 OK with a `text/plain` body.
+pub fn text<S: Into<String>>(body: S) -> Response {
+    with_type("text/plain; charset=utf-8", body.into())
+}
+
+// a 200 OK with a `text/html` body.
+pub fn html<S: Into<String>>(body: S) -> Response {
+    with_type("text/html; charset=utf-8", body.into())
+}
+
+// a 200 OK with `value` serialized as the body. failing to serialize is a 500.
+pub fn json<T: Serialize + ?Sized>(value: &T) -> Result<Response, Error> {
+    let body = serde_json::to_vec(value).map_err(Error::internal)?;
+    Ok(with_type("application/json", body))
+}
+
+// a 302 Found to `location`.
+pub fn redirect(location: &str) -> Result<Response, Error> {
+    redirect_with(StatusCode::FOUND, location)
+}
+
+// a 301 Moved Permanently to `location`.
+pub fn permanent_redirect(location: &str) -> Result<Response, Error> {
+    redirect_with(StatusCode::MOVED_PERMANENTLY, location)
+}
+
+// a response with just the status and an empty body.
+pub fn status(status: StatusCode) -> Response {
+    let mut res = Response::new(Body::empty());
+    *res.status_mut() = status;
+    res
+}
+
+fn with_type<B: Into<Body>>(content_type: &'static str, body: B) -> Response {
+    let mut res = Response::new(body.into());
+    res.headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    res
+}
+
+fn redirect_with(code: StatusCode, location: &str) -> Result<Response, Error> {
+    let location = HeaderValue::try_from(location).map_err(Error::internal)?;
+    let mut res = status(code);
+    res.headers_mut().insert(header::LOCATION, location);
+    Ok(res)
+}
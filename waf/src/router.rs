@@ -1,129 +1,201 @@
 extern crate hyper;
 extern crate path_tree;
 
-use hyper::http::Error;
-use hyper::Method;
-type Request = hyper::Request<hyper::Body>;
-type Response = hyper::Response<hyper::Body>;
+use hyper::{Method, StatusCode};
 use path_tree::PathTree;
-
-use super::context::Context;
-
-// TODO: handle http error status in middleware
-type Middleware = Box<dyn Fn(&mut Context, &Request) -> Result<(), Error> + Send + Sync>;
-type Handler = Box<dyn Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync>;
+use percent_encoding::percent_decode_str;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::Utf8Error;
+use std::sync::Arc;
+
+use super::context::{Context, States};
+use super::error::Error;
+use super::handler::Handler;
+use super::middleware::{Middleware, Next};
+use super::response::{self, IntoResponse};
+use super::{Request, Response};
 
 pub struct Router {
-    middlewares: PathTree<Middleware>,
-    routes: PathTree<Handler>,
+    // each middleware with a tree holding only its own path.
+    middlewares: Vec<(PathTree<()>, Arc<dyn Middleware>)>,
+    routes: PathTree<Arc<dyn Handler>>,
+    states: Arc<States>,
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Router {
     pub fn new() -> Self {
         Self {
-            middlewares: PathTree::new(),
+            middlewares: Vec::new(),
             routes: PathTree::new(),
+            states: Arc::new(HashMap::new()),
         }
     }
 
-    pub fn middleware<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    // adds a value shared by all requests, which handlers get with `State<T>` or
+    // `Context::state`. a later state of the same type replaces the earlier one.
+    pub fn state<T>(&mut self, state: T) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.states).insert(TypeId::of::<T>(), Arc::new(state));
+        self
+    }
+
+    // adds a middleware for the requests matching `path`. the middlewares of a request run
+    // in the order they were added, each one either calling `next.run(ctx, req)` for the
+    // response of the rest of the chain or returning its own response. like a handler, it
+    // can return anything implementing `IntoResponse`, such as `Result<Response, Error>`.
+    pub fn middleware<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&mut Context, &Request) -> Result<(), Error> + Send + Sync + 'static,
+        H: Fn(Context, Request, Next) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
-        self.middlewares.insert(path.into(), Box::new(handler));
+        let mut tree = PathTree::new();
+        tree.insert(path.into(), ());
+        self.middlewares.push((tree, Arc::new(handler)));
         self
     }
 
-    pub fn get<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn get<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::GET, path, handler)
     }
 
-    pub fn head<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn head<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::HEAD, path, handler)
     }
 
-    pub fn post<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn post<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::POST, path, handler)
     }
 
-    pub fn put<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn put<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::PUT, path, handler)
     }
 
-    pub fn patch<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn patch<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::PATCH, path, handler)
     }
 
-    pub fn delete<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn delete<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::DELETE, path, handler)
     }
 
-    pub fn options<'a, S, H>(&mut self, path: S, handler: H) -> &mut Self
+    pub fn options<'a, S, H, F>(&mut self, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.request(&Method::OPTIONS, path, handler)
     }
 
-    pub fn request<'a, S, H>(&mut self, method: &Method, path: S, handler: H) -> &mut Self
+    pub fn request<'a, S, H, F>(&mut self, method: &Method, path: S, handler: H) -> &mut Self
     where
         S: Into<&'a str>,
-        H: Fn(&Context, &Request) -> Result<Response, Error> + Send + Sync + 'static,
+        H: Fn(Context, Request) -> F + Send + Sync + 'static,
+        F: Future + Send + 'static,
+        F::Output: IntoResponse,
     {
         self.routes
-            .insert(&tree_path(method, path.into()), Box::new(handler));
+            .insert(&tree_path(method, path.into()), Arc::new(handler));
         self
     }
 
-    pub fn exec(&self, req: &Request) -> Result<Response, Error> {
-        let mut ctx = Context::new();
+    // runs the middlewares and the handler matching the request, converting what each one
+    // returns with `IntoResponse`.
+    pub async fn exec(&self, req: Request) -> Response {
+        let path = req.uri().path();
+        let middlewares: Vec<Arc<dyn Middleware>> = self
+            .middlewares
+            .iter()
+            .filter(|(tree, _)| tree.find(path).is_some())
+            .map(|(_, middleware)| middleware.clone())
+            .collect();
 
-        match self.middlewares.find(req.uri().path()) {
-            Some((middleware, _)) => match middleware(&mut ctx, req) {
-                Ok(_) => (),
-                Err(err) => return Err(err),
+        let mut ctx = Context::new();
+        ctx.set_states(self.states.clone());
+        let endpoint: Arc<dyn Handler> = match self.routes.find(&tree_path(req.method(), path)) {
+            Some((handler, params)) => match decode_params(&params) {
+                Ok(params) => {
+                    ctx.set_params(params);
+                    handler.clone()
+                }
+                Err(_) => Arc::new(bad_params),
             },
-            None => (),
-        }
-
-        match self.routes.find(&tree_path(req.method(), req.uri().path())) {
-            Some((handler, params)) => {
-                ctx.set_params(params);
-                handler(&ctx, &req)
-            }
-            None => hyper::Response::builder()
-                .status(hyper::StatusCode::NOT_FOUND)
-                .body(hyper::Body::from("Not Found")),
-        }
+            None => Arc::new(not_found),
+        };
+        Next::new(middlewares.into(), endpoint).run(ctx, req).await
     }
 }
 
+async fn not_found(_: Context, _: Request) -> Response {
+    response::status(StatusCode::NOT_FOUND)
+}
+
+async fn bad_params(_: Context, _: Request) -> Response {
+    Error::bad_request("path parameters are not valid UTF-8").into_response()
+}
+
+// the route matches the raw path, so each parameter is still percent-encoded.
+fn decode_params(params: &[(&str, &str)]) -> Result<Vec<(String, String)>, Utf8Error> {
+    params
+        .iter()
+        .map(|(k, v)| {
+            Ok((
+                k.to_string(),
+                percent_decode_str(v).decode_utf8()?.into_owned(),
+            ))
+        })
+        .collect()
+}
+
 #[inline]
 fn tree_path(method: &Method, path: &str) -> String {
     format!("/{}{}", method, path)
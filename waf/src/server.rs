@@ -1,26 +1,61 @@
-use super::router::Router;
-use hyper::rt::Future;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
 use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+use super::body::Body;
+use super::router::Router;
 
 pub struct Server {}
 
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Server {
     pub fn new() -> Self {
         Self {}
     }
 
-    pub fn serve(self, router: Router, port: u16) {
-        let router = Arc::new(router);
+    // serves `router` on localhost. it only returns on an error binding the port.
+    pub async fn serve(self, router: Router, port: u16) -> io::Result<()> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+        self.serve_listener(router, listener).await
+    }
 
-        let svc = move || {
+    // serves `router` on an already bound listener, e.g. one on port 0, each connection in
+    // its own task. it never returns: errors accepting connections, such as running out of
+    // file descriptors, are logged and retried after a pause.
+    pub async fn serve_listener(self, router: Router, listener: TcpListener) -> io::Result<()> {
+        let router = Arc::new(router);
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    eprintln!("accept error: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
             let router = router.clone();
-            service_fn(move |req| router.exec(&req))
-        };
-        let server = hyper::Server::bind(&([127, 0, 0, 1], port).into())
-            .serve(svc)
-            .map_err(|e| eprintln!("server error: {}", e));
-
-        hyper::rt::run(server);
+            tokio::spawn(async move {
+                let svc = service_fn(move |req: hyper::Request<Incoming>| {
+                    let router = router.clone();
+                    async move { Ok::<_, Infallible>(router.exec(req.map(Body::new)).await) }
+                });
+                let conn = http1::Builder::new().serve_connection(TokioIo::new(stream), svc);
+                if let Err(e) = conn.await {
+                    eprintln!("server error: {}", e);
+                }
+            });
+        }
     }
 }
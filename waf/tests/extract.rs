@@ -0,0 +1,249 @@
+use http_body_util::BodyExt;
+use hyper::{Request, Response, StatusCode};
+use serde::Deserialize;
+use waf::{extract, Bearer, Body, Header, Json, Path, Query, Router, State};
+
+async fn text(res: waf::Response) -> (StatusCode, String) {
+    let status = res.status();
+    let bs = res.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(bs.to_vec()).unwrap())
+}
+
+async fn send(router: &Router, req: Request<Body>) -> (StatusCode, String) {
+    text(router.exec(req).await).await
+}
+
+fn get(path: &str) -> Request<Body> {
+    Request::get(path).body(Body::empty()).unwrap()
+}
+
+#[derive(Deserialize)]
+struct Page {
+    offset: u32,
+    limit: Option<u32>,
+}
+
+#[tokio::test]
+async fn test_path_and_query() {
+    let mut router = Router::new();
+    router.get(
+        "/users/:id",
+        extract(|Path(id): Path<u64>, Query(page): Query<Page>| async move {
+            let body = format!("{} {} {:?}", id, page.offset, page.limit);
+            Response::builder().body(Body::from(body))
+        }),
+    );
+
+    let res = send(&router, get("/users/7?offset=2")).await;
+    assert_eq!((StatusCode::OK, "7 2 None".to_string()), res);
+    let res = send(&router, get("/users/x?offset=2")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert!(
+        res.1.starts_with("invalid path parameter `id`: "),
+        "{}",
+        res.1
+    );
+    let res = send(&router, get("/users/7?offset=a")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert!(res.1.starts_with("invalid query string: "), "{}", res.1);
+}
+
+#[derive(Deserialize)]
+struct Member {
+    team: String,
+    id: u32,
+}
+
+#[tokio::test]
+async fn test_path_params() {
+    let mut router = Router::new();
+    router.get(
+        "/teams/:team/members/:id",
+        extract(|Path((team, id)): Path<(String, u32)>| async move {
+            Response::builder().body(Body::from(format!("{} {}", team, id)))
+        }),
+    );
+    router.get(
+        "/orgs/:team/users/:id",
+        extract(|Path(member): Path<Member>| async move {
+            Response::builder().body(Body::from(format!("{} {}", member.team, member.id)))
+        }),
+    );
+    router.get(
+        "/pairs/:a/:b",
+        extract(
+            |Path(n): Path<u32>| async move { Response::builder().body(Body::from(n.to_string())) },
+        ),
+    );
+
+    let res = send(&router, get("/teams/core/members/3")).await;
+    assert_eq!((StatusCode::OK, "core 3".to_string()), res);
+    let res = send(&router, get("/orgs/core/users/4")).await;
+    assert_eq!((StatusCode::OK, "core 4".to_string()), res);
+    let res = send(&router, get("/teams/core/members/-3")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert!(
+        res.1.starts_with("invalid path parameter `id`: "),
+        "{}",
+        res.1
+    );
+    let res = send(&router, get("/orgs/core/users/x")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert!(
+        res.1.starts_with("invalid path parameter `id`: "),
+        "{}",
+        res.1
+    );
+    // the type doesn't fit the route.
+    let res = send(&router, get("/pairs/1/2")).await;
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.0);
+}
+
+#[tokio::test]
+async fn test_encoded_path_params() {
+    let mut router = Router::new();
+    router.get(
+        "/files/:name",
+        extract(
+            |Path(name): Path<String>| async move { Response::builder().body(Body::from(name)) },
+        ),
+    );
+    router.get(
+        "/teams/:team/members/:id",
+        extract(|Path((team, id)): Path<(String, u32)>| async move {
+            Response::builder().body(Body::from(format!("{} {}", team, id)))
+        }),
+    );
+
+    let res = send(&router, get("/files/a%20b")).await;
+    assert_eq!((StatusCode::OK, "a b".to_string()), res);
+    let res = send(&router, get("/files/%E6%97%A5%2F")).await;
+    assert_eq!((StatusCode::OK, "日/".to_string()), res);
+    let res = send(&router, get("/teams/a+b/members/%31")).await;
+    assert_eq!((StatusCode::OK, "a+b 1".to_string()), res);
+    let res = send(&router, get("/files/%FF")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert_eq!("path parameters are not valid UTF-8", res.1);
+}
+
+#[derive(Deserialize)]
+struct User {
+    name: String,
+}
+
+#[tokio::test]
+async fn test_json() {
+    let mut router = Router::new();
+    router.post(
+        "/users",
+        extract(
+            |Json(user): Json<User>| async move { Response::builder().body(Body::from(user.name)) },
+        ),
+    );
+    let post = |content_type: &str, body: &'static str| {
+        Request::post("/users")
+            .header("content-type", content_type)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let res = send(&router, post("application/json", r#"{"name":"a"}"#)).await;
+    assert_eq!((StatusCode::OK, "a".to_string()), res);
+    let res = send(&router, post("application/json", r#"{"age":1}"#)).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.0);
+    assert!(
+        res.1.starts_with("invalid JSON body: missing field `name`"),
+        "{}",
+        res.1
+    );
+    let res = send(
+        &router,
+        post("Application/JSON; charset=utf-8", r#"{"name":"b"}"#),
+    )
+    .await;
+    assert_eq!((StatusCode::OK, "b".to_string()), res);
+    for content_type in &["text/plain", "application/jsonx", "application/json-seq"] {
+        let res = send(&router, post(content_type, "{}")).await;
+        assert_eq!(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            res.0,
+            "{}",
+            content_type
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_header_and_state() {
+    let mut router = Router::new();
+    router.state("secret".to_string());
+    router.get(
+        "/me",
+        extract(
+            |State(secret): State<String>, Header(Bearer(token)): Header<Bearer>| async move {
+                let status = if token == secret {
+                    StatusCode::OK
+                } else {
+                    StatusCode::FORBIDDEN
+                };
+                Response::builder().status(status).body(Body::empty())
+            },
+        ),
+    );
+    let with_auth = |auth: &str| {
+        Request::get("/me")
+            .header("authorization", auth)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    assert_eq!(
+        StatusCode::OK,
+        send(&router, with_auth("Bearer secret")).await.0
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        send(&router, with_auth("Bearer x")).await.0
+    );
+    assert_eq!(
+        StatusCode::OK,
+        send(&router, with_auth("bearer secret")).await.0
+    );
+    assert_eq!(
+        StatusCode::BAD_REQUEST,
+        send(&router, with_auth("Bearer ")).await.0
+    );
+    let res = send(&router, with_auth("Basic x")).await;
+    assert_eq!(
+        (
+            StatusCode::BAD_REQUEST,
+            "invalid header `authorization`: expected a bearer token".to_string()
+        ),
+        res
+    );
+    let res = send(&router, get("/me")).await;
+    assert_eq!(
+        (
+            StatusCode::BAD_REQUEST,
+            "missing header `authorization`".to_string()
+        ),
+        res
+    );
+
+    let mut router = Router::new();
+    router.get(
+        "/",
+        extract(|State(n): State<u32>| async move {
+            Response::builder().body(Body::from(n.to_string()))
+        }),
+    );
+    let res = send(&router, get("/")).await;
+    // a missing state is a bug of the server, so the client doesn't see the cause.
+    assert_eq!(
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal Server Error".to_string()
+        ),
+        res
+    );
+}
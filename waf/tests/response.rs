@@ -0,0 +1,143 @@
+use http_body_util::BodyExt;
+use hyper::{Request, StatusCode};
+use serde::Deserialize;
+use std::fmt;
+use waf::{extract, html, json, redirect, status, text, Body, Error, Json, Router};
+
+async fn send(router: &Router, req: Request<Body>) -> waf::Response {
+    router.exec(req).await
+}
+
+async fn body(res: waf::Response) -> String {
+    let bs = res.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(bs.to_vec()).unwrap()
+}
+
+fn get(path: &str) -> Request<Body> {
+    Request::get(path).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_constructors() {
+    let mut router = Router::new();
+    router
+        .get("/text", |_, _| async { text("a") })
+        .get("/html", |_, _| async { html("<p>a</p>") })
+        .get("/json", |_, _| async { json(&[1, 2]) })
+        .get("/redirect", |_, _| async { redirect("/text") })
+        .get("/status", |_, _| async { status(StatusCode::NO_CONTENT) })
+        .get("/tuple", |_, _| async { (StatusCode::CREATED, "made") });
+
+    let res = send(&router, get("/text")).await;
+    assert_eq!("text/plain; charset=utf-8", res.headers()["content-type"]);
+    assert_eq!("a", body(res).await);
+    let res = send(&router, get("/html")).await;
+    assert_eq!("text/html; charset=utf-8", res.headers()["content-type"]);
+    let res = send(&router, get("/json")).await;
+    assert_eq!("application/json", res.headers()["content-type"]);
+    assert_eq!("[1,2]", body(res).await);
+    let res = send(&router, get("/redirect")).await;
+    assert_eq!(StatusCode::FOUND, res.status());
+    assert_eq!("/text", res.headers()["location"]);
+    let res = send(&router, get("/status")).await;
+    assert_eq!(StatusCode::NO_CONTENT, res.status());
+    assert_eq!("", body(res).await);
+    let res = send(&router, get("/tuple")).await;
+    assert_eq!(StatusCode::CREATED, res.status());
+    assert_eq!("made", body(res).await);
+}
+
+#[derive(Debug)]
+enum AppError {
+    NoSuchUser(u32),
+    Database,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::NoSuchUser(id) => write!(f, "no user {}", id),
+            AppError::Database => f.write_str("database down"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<AppError> for Error {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::NoSuchUser(_) => Error::not_found(e.to_string()),
+            AppError::Database => Error::internal(e),
+        }
+    }
+}
+
+fn find_user(id: u32) -> Result<String, AppError> {
+    match id {
+        1 => Ok("a".to_string()),
+        2 => Err(AppError::Database),
+        id => Err(AppError::NoSuchUser(id)),
+    }
+}
+
+#[tokio::test]
+async fn test_errors() {
+    let mut router = Router::new();
+    router.get("/users/:id", |ctx, _| async move {
+        let id = ctx
+            .param("id")
+            .unwrap()
+            .parse()
+            .map_err(|_| Error::bad_request("the id is not a number"))?;
+        Ok::<_, Error>(text(find_user(id)?))
+    });
+
+    let res = send(&router, get("/users/1")).await;
+    assert_eq!(StatusCode::OK, res.status());
+    assert_eq!("a", body(res).await);
+    let res = send(&router, get("/users/x")).await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+    assert_eq!("the id is not a number", body(res).await);
+    let res = send(&router, get("/users/3")).await;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+    assert_eq!("no user 3", body(res).await);
+    // the cause of a 500 stays on the server.
+    let res = send(&router, get("/users/2")).await;
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+    assert_eq!("Internal Server Error", body(res).await);
+
+    let e = Error::from(AppError::Database);
+    assert_eq!("500 Internal Server Error: database down", e.to_string());
+}
+
+#[derive(Deserialize)]
+struct User {
+    name: String,
+}
+
+#[tokio::test]
+async fn test_body_limit() {
+    let bs = Body::from("abcd").bytes(4).await.unwrap();
+    assert_eq!(&b"abcd"[..], &bs[..]);
+    let e = Body::from("abcd").bytes(3).await.unwrap_err();
+    assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, e.status());
+    assert_eq!("body larger than 3 bytes", e.message());
+    let e = Body::from(vec![0xff]).text(10).await.unwrap_err();
+    assert_eq!(StatusCode::BAD_REQUEST, e.status());
+    let user: User = Body::from(r#"{"name":"a"}"#).json(100).await.unwrap();
+    assert_eq!("a", user.name);
+
+    let mut router = Router::new();
+    router.post("/echo", extract(|s: String| async move { Json(vec![s]) }));
+    let req = Request::post("/echo").body(Body::from("hi")).unwrap();
+    let res = send(&router, req).await;
+    assert_eq!("application/json", res.headers()["content-type"]);
+    assert_eq!(r#"["hi"]"#, body(res).await);
+    let big = vec![b'a'; waf::DEFAULT_LIMIT + 1];
+    let req = Request::post("/echo").body(Body::from(big)).unwrap();
+    assert_eq!(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        send(&router, req).await.status()
+    );
+}
@@ -0,0 +1,186 @@
+use http_body_util::BodyExt;
+use hyper::header::HeaderValue;
+use hyper::{Request, Response, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use waf::{blocking, status, Body, Router, Server};
+
+async fn get(router: &Router, path: &str) -> waf::Response {
+    router
+        .exec(Request::get(path).body(Body::empty()).unwrap())
+        .await
+}
+
+async fn text(res: waf::Response) -> String {
+    let bs = res.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(bs.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_middleware_order() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut router = Router::new();
+    for name in &["a", "b"] {
+        let log = log.clone();
+        router.middleware("/*", move |ctx, req, next| {
+            let log = log.clone();
+            async move {
+                log.lock().unwrap().push(format!("{} before", name));
+                let res = next.run(ctx, req).await;
+                log.lock().unwrap().push(format!("{} after", name));
+                res
+            }
+        });
+    }
+    let handler_log = log.clone();
+    router.get("/x", move |_, _| {
+        handler_log.lock().unwrap().push("handler".to_string());
+        async { Response::builder().body(Body::from("x")) }
+    });
+
+    assert_eq!(StatusCode::OK, get(&router, "/x").await.status());
+    assert_eq!(
+        vec!["a before", "b before", "handler", "b after", "a after"],
+        *log.lock().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_short_circuit() {
+    let mut router = Router::new();
+    router
+        .middleware("/admin/*", |ctx, req, next| async move {
+            if req.headers().contains_key("Authorization") {
+                next.run(ctx, req).await
+            } else {
+                status(StatusCode::UNAUTHORIZED)
+            }
+        })
+        .get("/admin/:page", |_, _| async {
+            Response::builder().body(Body::empty())
+        })
+        .get("/public", |_, _| async {
+            Response::builder().body(Body::empty())
+        });
+
+    let res = get(&router, "/admin/users").await;
+    assert_eq!(StatusCode::UNAUTHORIZED, res.status());
+    assert_eq!(StatusCode::OK, get(&router, "/public").await.status());
+
+    let req = Request::get("/admin/users")
+        .header("Authorization", "Bearer x")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(StatusCode::OK, router.exec(req).await.status());
+}
+
+#[tokio::test]
+async fn test_post_process() {
+    let mut router = Router::new();
+    router
+        .middleware("/*", |ctx, req, next| async move {
+            let mut res = next.run(ctx, req).await;
+            res.headers_mut()
+                .insert("X-Powered-By", HeaderValue::from_static("waf"));
+            res
+        })
+        .middleware("/*", |mut ctx, req, next| {
+            ctx.set_value("user", 7u32);
+            next.run(ctx, req)
+        })
+        .get("/users/:id", |ctx, _| async move {
+            let id = ctx.param("id").unwrap();
+            let user: &u32 = ctx.value("user").unwrap();
+            Response::builder().body(Body::from(format!("{} {}", id, user)))
+        });
+
+    let res = get(&router, "/users/1").await;
+    assert_eq!("waf", res.headers()["X-Powered-By"]);
+    assert_eq!("1 7", text(res).await);
+    // the not-found response goes through the middlewares too.
+    let res = get(&router, "/missing").await;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+    assert_eq!("waf", res.headers()["X-Powered-By"]);
+    // with an empty body, like `StatusCode::NOT_FOUND.into_response()`.
+    assert_eq!("", text(res).await);
+}
+
+#[tokio::test]
+async fn test_middleware_error() {
+    let mut router = Router::new();
+    router
+        .middleware("/*", |ctx, req: waf::Request, next| async move {
+            let n: u32 = req
+                .headers()
+                .get("X-N")
+                .and_then(|val| val.to_str().ok())
+                .and_then(|val| val.parse().ok())
+                .ok_or_else(|| waf::Error::bad_request("missing X-N"))?;
+            let res = next.run(ctx, req).await;
+            Ok::<_, waf::Error>((res.status(), n.to_string()))
+        })
+        .get("/", |_, _| async { StatusCode::ACCEPTED });
+
+    let res = get(&router, "/").await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+    assert_eq!("missing X-N", text(res).await);
+    let req = Request::get("/")
+        .header("X-N", "3")
+        .body(Body::empty())
+        .unwrap();
+    let res = router.exec(req).await;
+    assert_eq!(StatusCode::ACCEPTED, res.status());
+    assert_eq!("3", text(res).await);
+}
+
+#[tokio::test]
+async fn test_error_status() {
+    let mut router = Router::new();
+    router.get("/bad", |_, _| async {
+        Response::builder()
+            .header("Bad Header", "x")
+            .body(Body::empty())
+    });
+    let res = get(&router, "/bad").await;
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+}
+
+#[tokio::test]
+async fn test_blocking() {
+    let mut router = Router::new();
+    router.get(
+        "/sleep/:ms",
+        blocking(|ctx, _| {
+            let ms = ctx.param("ms").unwrap().parse().unwrap();
+            std::thread::sleep(Duration::from_millis(ms));
+            Response::builder().body(Body::from("slept"))
+        }),
+    );
+    assert_eq!("slept", text(get(&router, "/sleep/10").await).await);
+}
+
+#[tokio::test]
+async fn test_serve() {
+    let mut router = Router::new();
+    router.get("/hello", |_, _| async {
+        Response::builder().body(Body::from("hello"))
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(Server::new().serve_listener(router, listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut buf = String::new();
+    tokio::time::timeout(Duration::from_secs(10), stream.read_to_string(&mut buf))
+        .await
+        .expect("no response within 10s")
+        .unwrap();
+    assert!(buf.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(buf.ends_with("\r\n\r\nhello"));
+}